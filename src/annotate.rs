@@ -0,0 +1,262 @@
+//! Game review: every move is compared with what the engine would have played, and the ones
+//! that threw away too much of the evaluation are marked as inaccuracies, mistakes or
+//! blunders.
+
+use crate::engine::{Engine, Limits, Score};
+use crate::pgn::{self, PgnMove};
+use crate::{Board, BoardState, Color, Error, Move};
+
+/// Evaluations are capped here, so a missed mate costs a lot without dwarfing everything else.
+const CAP: i32 = 1000;
+
+/// How bad a move was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// The usual symbol, like `??` for a blunder.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "?!",
+            Judgement::Mistake => "?",
+            Judgement::Blunder => "??",
+        }
+    }
+
+    /// The Numeric Annotation Glyph PGN writes for it.
+    pub fn nag(&self) -> u8 {
+        match self {
+            Judgement::Inaccuracy => 6,
+            Judgement::Mistake => 2,
+            Judgement::Blunder => 4,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "Inaccuracy",
+            Judgement::Mistake => "Mistake",
+            Judgement::Blunder => "Blunder",
+        }
+    }
+}
+
+/// How many centipawns a move has to lose to count as each kind of bad move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    pub inaccuracy: i32,
+    pub mistake: i32,
+    pub blunder: i32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            inaccuracy: 50,
+            mistake: 100,
+            blunder: 300,
+        }
+    }
+}
+
+impl Thresholds {
+    fn judge(&self, loss: i32) -> Option<Judgement> {
+        if loss >= self.blunder {
+            Some(Judgement::Blunder)
+        } else if loss >= self.mistake {
+            Some(Judgement::Mistake)
+        } else if loss >= self.inaccuracy {
+            Some(Judgement::Inaccuracy)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotatedMove {
+    pub m: Move,
+    pub san: String,
+    pub color: Color,
+    /// The evaluation with the best move, for the side that moved, in centipawns.
+    pub best_eval: i32,
+    /// The evaluation after the move played, for the side that moved.
+    pub played_eval: i32,
+    /// How much the move gave away, never negative.
+    pub loss: i32,
+    /// How close the move came to the best one, from 0 to 100.
+    pub accuracy: f64,
+    pub judgement: Option<Judgement>,
+    /// What the engine would have played instead, in SAN, for moves that got a judgement.
+    pub best_line: Vec<String>,
+}
+
+/// How one player did over the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Summary {
+    pub inaccuracies: u32,
+    pub mistakes: u32,
+    pub blunders: u32,
+    /// Average centipawn loss per move.
+    pub average_loss: f64,
+    /// The average of the moves' accuracies, from 0 to 100.
+    pub accuracy: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// The starting position in FEN.
+    pub start: String,
+    pub moves: Vec<AnnotatedMove>,
+    pub white: Summary,
+    pub black: Summary,
+    /// `1-0`, `0-1`, `1/2-1/2`, or `*` if the game didn't finish.
+    pub result: &'static str,
+}
+
+/// Turns a score for the side to move into capped centipawns.
+fn centipawns(score: Score) -> i32 {
+    match score {
+        Score::Centipawns(centipawns) => centipawns.clamp(-CAP, CAP),
+        Score::Mate(moves) if moves > 0 => CAP,
+        Score::Mate(_) => -CAP,
+    }
+}
+
+/// The chance of winning with an evaluation, from 0 to 100.
+fn win_percent(centipawns: i32) -> f64 {
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.00368208 * centipawns as f64).exp()) - 1.0)
+}
+
+/// A move's accuracy from how much it lowered the chance of winning.
+fn move_accuracy(best: i32, played: i32) -> f64 {
+    let drop = (win_percent(best) - win_percent(played)).max(0.0);
+    (103.1668 * (-0.04354 * drop).exp() - 3.1669).clamp(0.0, 100.0)
+}
+
+fn summarize(moves: &[&AnnotatedMove]) -> Summary {
+    let mut summary = Summary::default();
+    for m in moves {
+        match m.judgement {
+            Some(Judgement::Inaccuracy) => summary.inaccuracies += 1,
+            Some(Judgement::Mistake) => summary.mistakes += 1,
+            Some(Judgement::Blunder) => summary.blunders += 1,
+            None => {}
+        }
+    }
+    if !moves.is_empty() {
+        let count = moves.len() as f64;
+        summary.average_loss = moves.iter().map(|m| m.loss as f64).sum::<f64>() / count;
+        summary.accuracy = moves.iter().map(|m| m.accuracy).sum::<f64>() / count;
+    }
+    summary
+}
+
+/// Reviews the game played from `start` with `moves`, searching every position `depth` plies
+/// deep.
+pub fn annotate(
+    start: &Board,
+    moves: &[Move],
+    depth: u32,
+    thresholds: Thresholds,
+) -> Result<Annotation, Error> {
+    let mut boards = vec![start.clone()];
+    for m in moves {
+        let mut board = boards.last().unwrap().clone();
+        board.make_move(*m)?;
+        boards.push(board);
+    }
+
+    // Each position is searched once: its score is the best the side to move can do, and
+    // the negated score is what the move leading there was worth
+    let mut engine = Engine::default();
+    let searches = boards
+        .iter()
+        .map(|board| match board.state {
+            BoardState::Checkmate(_) | BoardState::VariantLoss(_) => Ok((-CAP, vec![])),
+            BoardState::VariantWin(_) => Ok((CAP, vec![])),
+            BoardState::Stalemate | BoardState::Draw => Ok((0, vec![])),
+            _ => {
+                let result = engine.search(board, Limits::depth(depth))?;
+                Ok((centipawns(result.score), result.pv))
+            }
+        })
+        .collect::<Result<Vec<(i32, Vec<Move>)>, Error>>()?;
+
+    let mut annotated = vec![];
+    for (index, m) in moves.iter().enumerate() {
+        let board = &boards[index];
+        let (best_eval, best_pv) = &searches[index];
+        let best_eval = *best_eval;
+        let played_best = best_pv.first() == Some(m);
+        let played_eval = match played_best {
+            true => best_eval,
+            false => -searches[index + 1].0,
+        };
+        let loss = (best_eval - played_eval).max(0);
+        let judgement = thresholds.judge(loss);
+        let best_line = match judgement {
+            Some(_) => board.san_line(best_pv)?,
+            None => vec![],
+        };
+        annotated.push(AnnotatedMove {
+            m: *m,
+            san: board.san(*m)?,
+            color: board.side_to_move(),
+            best_eval,
+            played_eval,
+            loss,
+            accuracy: move_accuracy(best_eval, played_eval),
+            judgement,
+            best_line,
+        });
+    }
+
+    let by = |color: Color| -> Vec<&AnnotatedMove> {
+        annotated.iter().filter(|m| m.color == color).collect()
+    };
+    let white = summarize(&by(Color::White));
+    let black = summarize(&by(Color::Black));
+    let result = match boards.last().unwrap().state {
+        BoardState::Checkmate(Color::Black)
+        | BoardState::VariantLoss(Color::Black)
+        | BoardState::VariantWin(Color::White) => "1-0",
+        BoardState::Checkmate(Color::White)
+        | BoardState::VariantLoss(Color::White)
+        | BoardState::VariantWin(Color::Black) => "0-1",
+        BoardState::Stalemate | BoardState::Draw => "1/2-1/2",
+        BoardState::ToMove(_) | BoardState::Check(_) => "*",
+    };
+    Ok(Annotation {
+        start: start.to_fen(),
+        moves: annotated,
+        white,
+        black,
+        result,
+    })
+}
+
+impl Annotation {
+    /// The game as PGN, with a NAG and a comment naming the better line after each bad move.
+    /// See [`pgn::write`] for how `tags` are used.
+    pub fn pgn(&self, tags: &[(&str, &str)]) -> String {
+        let moves: Vec<PgnMove> = self
+            .moves
+            .iter()
+            .map(|m| PgnMove {
+                color: m.color,
+                san: m.san.clone(),
+                nag: m.judgement.map(|judgement| judgement.nag()),
+                comment: m.judgement.map(|judgement| match m.best_line.is_empty() {
+                    true => format!("{}.", judgement.name()),
+                    false => format!("{}. Best was {}.", judgement.name(), m.best_line.join(" ")),
+                }),
+            })
+            .collect();
+        pgn::write(&self.start, &moves, self.result, tags)
+    }
+}
@@ -0,0 +1,38 @@
+//! Antichess, where the aim is to lose every piece. Capturing is compulsory whenever possible,
+//! nothing is ever in check, and the king is an ordinary piece that can be captured and that
+//! pawns may promote to. Running out of pieces or of moves wins.
+
+use crate::{Board, Color, Piece, Position, Troop};
+
+impl Board {
+    /// Whether `troop` going to `to` captures something, en passant included.
+    pub(crate) fn captures(&self, troop: &Troop, to: Position) -> bool {
+        match &self.get_square(&to).troop {
+            Some(other) => other.color != troop.color,
+            None => troop.piece == Piece::Pawn && Some(to) == self.en_passant,
+        }
+    }
+
+    /// The squares `troop` can move to in Antichess, before captures are made compulsory. With
+    /// no king to keep safe that's everywhere it reaches, except that there's no castling.
+    pub(crate) fn antichess_destinations(&self, troop: &Troop) -> Vec<Position> {
+        self.reachable_squares(troop)
+            .into_iter()
+            .map(|square| square.position)
+            .collect()
+    }
+
+    /// Whether `color` has a capture, and so has to make one.
+    pub(crate) fn can_capture(&self, color: Color) -> bool {
+        self.squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+            .filter(|troop| troop.color == color)
+            .any(|troop| {
+                self.antichess_destinations(troop)
+                    .into_iter()
+                    .any(|to| self.captures(troop, to))
+            })
+    }
+}
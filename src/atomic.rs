@@ -0,0 +1,82 @@
+//! Atomic chess, where every capture is an explosion that takes out the capturing piece and
+//! every piece other than a pawn next to the square. Blowing up the enemy king wins, so kings
+//! can't capture, and kings standing next to each other can't be checked.
+
+use crate::{Board, Color, Move, Piece, Position, Troop, Variant};
+
+impl Board {
+    /// Whether `color`'s king is still on the board. Only an explosion can take it off.
+    pub(crate) fn king_alive(&self, color: Color) -> bool {
+        self.king(color).is_some()
+    }
+
+    /// Blows up the capture on `center` in Atomic, returning the squares emptied.
+    pub(crate) fn explode(&mut self, center: Position) -> Vec<Position> {
+        if self.variant != Variant::Atomic {
+            return vec![];
+        }
+        let mut exploded = vec![center];
+        self.get_mut_square(&center).troop = None;
+        for files in -1..=1 {
+            for ranks in -1..=1 {
+                let Some(position) = center.offset(files, ranks) else {
+                    continue;
+                };
+                let square = self.get_mut_square(&position);
+                if square
+                    .troop
+                    .as_ref()
+                    .is_some_and(|troop| troop.piece != Piece::Pawn)
+                {
+                    square.troop = None;
+                    exploded.push(position);
+                }
+            }
+        }
+        exploded
+    }
+
+    /// Check in Atomic: a king can't be checked once it's gone, or while it stands next to the
+    /// enemy king, since capturing it would blow up the capturer's own king too.
+    pub(crate) fn atomic_check(&self, color: Color) -> bool {
+        let Some(king) = self.king(color) else {
+            return false;
+        };
+        let adjacent = self.king(color.opponent()).is_some_and(|enemy| {
+            (king.file as i8 - enemy.file as i8).abs() <= 1
+                && (king.rank as i8 - enemy.rank as i8).abs() <= 1
+        });
+        if adjacent {
+            return false;
+        }
+        self.is_attacked(king, color.opponent())
+    }
+
+    /// The squares `troop` can move to in Atomic. A move is legal if it keeps the mover's king on
+    /// the board, and either blows up the enemy king or doesn't leave its own in check.
+    pub(crate) fn atomic_destinations(&self, troop: &Troop) -> Vec<Position> {
+        let enemy = troop.color.opponent();
+        self.valid_moves(troop)
+            .into_iter()
+            .map(|square| square.position)
+            .filter(|to| {
+                let capture = self
+                    .get_square(to)
+                    .troop
+                    .as_ref()
+                    .is_some_and(|other| other.color == enemy);
+                if troop.piece == Piece::King && capture {
+                    return false;
+                }
+                let mut after = self.clone();
+                after.apply_move(Move {
+                    from: troop.position,
+                    to: *to,
+                    promotion: None,
+                });
+                after.king_alive(troop.color)
+                    && (!after.king_alive(enemy) || !after.check_for_check(troop.color))
+            })
+            .collect()
+    }
+}
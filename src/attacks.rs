@@ -0,0 +1,139 @@
+use crate::{Bitboard, Board, Color, Piece, Position, Troop};
+
+pub(crate) const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+pub(crate) const KING_OFFSETS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+pub(crate) const ROOK_DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+pub(crate) const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+impl Color {
+    /// Which way this color's pawns move along the ranks.
+    pub(crate) fn forward(&self) -> i8 {
+        match self {
+            Color::White => 1,
+            Color::Black => -1,
+        }
+    }
+}
+
+impl Board {
+    fn slides(piece: Piece, direction: (i8, i8)) -> bool {
+        match piece {
+            Piece::Queen => true,
+            Piece::Rook => ROOK_DIRECTIONS.contains(&direction),
+            Piece::Bishop => BISHOP_DIRECTIONS.contains(&direction),
+            _ => false,
+        }
+    }
+
+    /// The squares `troop` attacks, whether they're empty, hold an enemy, or hold a friendly
+    /// troop it defends. Pawns attack diagonally only, and castling never attacks anything.
+    pub fn attacks(&self, troop: &Troop) -> Bitboard {
+        let mut attacks = Bitboard::EMPTY;
+        let steps: &[(i8, i8)] = match troop.piece {
+            Piece::Knight => &KNIGHT_OFFSETS,
+            Piece::King => &KING_OFFSETS,
+            Piece::Pawn => {
+                let forward = troop.color.forward();
+                &[(-1, forward), (1, forward)]
+            }
+            Piece::Bishop | Piece::Rook | Piece::Queen => {
+                for direction in ROOK_DIRECTIONS.iter().chain(BISHOP_DIRECTIONS.iter()) {
+                    if !Self::slides(troop.piece, *direction) {
+                        continue;
+                    }
+                    let mut position = troop.position;
+                    while let Some(next) = position.offset(direction.0, direction.1) {
+                        attacks.insert(next);
+                        if self.get_square(&next).troop.is_some() {
+                            break;
+                        }
+                        position = next;
+                    }
+                }
+                return attacks;
+            }
+        };
+        for (files, ranks) in steps {
+            if let Some(position) = troop.position.offset(*files, *ranks) {
+                attacks.insert(position);
+            }
+        }
+        attacks
+    }
+
+    /// Every square attacked by at least one of `color`'s troops.
+    pub fn attack_map(&self, color: Color) -> Bitboard {
+        self.squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+            .filter(|troop| troop.color == color)
+            .fold(Bitboard::EMPTY, |map, troop| map | self.attacks(troop))
+    }
+
+    /// Every troop of either color attacking `position`.
+    pub fn attackers_of(&self, position: Position) -> Vec<&Troop> {
+        let mut attackers = vec![];
+        for direction in ROOK_DIRECTIONS.iter().chain(BISHOP_DIRECTIONS.iter()) {
+            let mut current = position;
+            while let Some(next) = current.offset(direction.0, direction.1) {
+                if let Some(troop) = &self.get_square(&next).troop {
+                    if Self::slides(troop.piece, *direction) {
+                        attackers.push(troop);
+                    }
+                    break;
+                }
+                current = next;
+            }
+        }
+        for (offsets, piece) in [(KNIGHT_OFFSETS, Piece::Knight), (KING_OFFSETS, Piece::King)] {
+            for (files, ranks) in offsets {
+                if let Some(next) = position.offset(files, ranks) {
+                    match &self.get_square(&next).troop {
+                        Some(troop) if troop.piece == piece => attackers.push(troop),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        for color in [Color::White, Color::Black] {
+            // A pawn attacks diagonally forward, so look diagonally backward from its target
+            for files in [-1, 1] {
+                if let Some(next) = position.offset(files, -color.forward()) {
+                    match &self.get_square(&next).troop {
+                        Some(troop) if troop.piece == Piece::Pawn && troop.color == color => {
+                            attackers.push(troop)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        attackers
+    }
+
+    /// Whether any of `by_color`'s troops attacks `position`.
+    pub fn is_attacked(&self, position: Position, by_color: Color) -> bool {
+        self.attackers_of(position)
+            .iter()
+            .any(|troop| troop.color == by_color)
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use rust_chess::client::{run, Options};
+use rust_chess::engine::Limits;
+use rust_chess::render::Glyphs;
+use rust_chess::Color;
+
+const USAGE: &str = "Usage: rust-chess-play [--engine white|black] [--depth N | --time SECONDS] \
+                     [--unicode] [--ansi]";
+
+fn options() -> Option<Options> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--engine" => {
+                options.engine = match args.next()?.as_str() {
+                    "white" => Some(Color::White),
+                    "black" => Some(Color::Black),
+                    _ => return None,
+                }
+            }
+            "--depth" => options.limits = Limits::depth(args.next()?.parse().ok()?),
+            "--time" => {
+                let seconds = args.next()?.parse().ok()?;
+                options.limits = Limits::time(Duration::try_from_secs_f64(seconds).ok()?);
+            }
+            "--unicode" => options.renderer.glyphs = Glyphs::Unicode,
+            "--ansi" => options.renderer.ansi = true,
+            _ => return None,
+        }
+    }
+    // Whoever plays against the engine as black sees the board from their side
+    if options.engine == Some(Color::White) {
+        options.renderer.perspective = Color::Black;
+    }
+    Some(options)
+}
+
+fn main() -> std::io::Result<()> {
+    let Some(options) = options() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    run(std::io::stdin().lock(), std::io::stdout(), options)
+}
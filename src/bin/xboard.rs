@@ -0,0 +1,3 @@
+fn main() -> std::io::Result<()> {
+    rust_chess::xboard::run(std::io::stdin().lock(), std::io::stdout())
+}
@@ -0,0 +1,99 @@
+use crate::{File, Position, Rank};
+
+/// A set of squares, one bit per square. Bit 0 is a1, bit 7 is h1 and bit 63 is h8.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.0 & (1 << position.index()) != 0
+    }
+
+    pub fn insert(&mut self, position: Position) {
+        self.0 |= 1 << position.index();
+    }
+
+    pub fn remove(&mut self, position: Position) {
+        self.0 &= !(1 << position.index());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// The squares in the set, from a1 to h8.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let bits = self.0;
+        (0..64)
+            .filter(move |index| bits & (1 << index) != 0)
+            .map(Position::from_index)
+    }
+}
+
+impl std::ops::BitOr for Bitboard {
+    type Output = Bitboard;
+    fn bitor(self, rhs: Bitboard) -> Bitboard {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Bitboard) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl std::ops::BitAnd for Bitboard {
+    type Output = Bitboard;
+    fn bitand(self, rhs: Bitboard) -> Bitboard {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl std::ops::Not for Bitboard {
+    type Output = Bitboard;
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl FromIterator<Position> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Position>>(iter: I) -> Self {
+        let mut bitboard = Bitboard::EMPTY;
+        for position in iter {
+            bitboard.insert(position);
+        }
+        bitboard
+    }
+}
+
+impl Position {
+    /// The square's index from 0 (a1) to 63 (h8), rank by rank.
+    pub fn index(&self) -> u8 {
+        self.rank as u8 * 8 + self.file as u8
+    }
+
+    pub fn from_index(index: u8) -> Position {
+        Position {
+            file: File::try_from(index % 8 + 1).unwrap(),
+            rank: Rank::try_from(index / 8 + 1).unwrap(),
+        }
+    }
+
+    /// The square `files` to the right and `ranks` up from this one, if it's on the board.
+    pub fn offset(&self, files: i8, ranks: i8) -> Option<Position> {
+        let file = u8::try_from(self.file as i8 + files + 1).ok()?;
+        let rank = u8::try_from(self.rank as i8 + ranks + 1).ok()?;
+        Some(Position {
+            file: File::try_from(file).ok()?,
+            rank: Rank::try_from(rank).ok()?,
+        })
+    }
+}
@@ -0,0 +1,265 @@
+//! Opening books in Polyglot's `.bin` format.
+//!
+//! A book is a list of 16-byte entries sorted by the Polyglot hash of a position: the key, a
+//! move, a weight and a learning value, all big-endian. Polyglot hashes with a fixed table of
+//! 781 random numbers, the `Random64` array from its reference implementation, which is bundled
+//! as [`RANDOM64`] and used by [`PolyglotKeys::default`]. Books hashed with a different table
+//! can still be read by loading it with [`PolyglotKeys::load`].
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+
+use crate::{Board, Color, Error, File, Move, Piece, Position, Rank};
+
+mod random64;
+pub use random64::RANDOM64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BookError {
+    Io,
+    /// The book's length isn't a whole number of entries.
+    Format,
+    /// The key table doesn't hold exactly 781 numbers.
+    Keys,
+}
+
+const KEY_COUNT: usize = 781;
+const CASTLING: usize = 768;
+const EN_PASSANT: usize = 772;
+const TURN: usize = 780;
+const ENTRY_SIZE: usize = 16;
+
+/// The random numbers Polyglot hashes positions with: 768 for the pieces on each square, then
+/// the four castling rights, the eight en passant files, and white to move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolyglotKeys(Vec<u64>);
+
+impl PolyglotKeys {
+    pub fn new(keys: Vec<u64>) -> Result<Self, Error> {
+        match keys.len() {
+            KEY_COUNT => Ok(PolyglotKeys(keys)),
+            _ => Err(Error::Book(BookError::Keys)),
+        }
+    }
+
+    /// Reads the numbers from text, taking every `0x`-prefixed hex literal in order, so the
+    /// array can be copied straight out of C, Rust or Python source.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let keys = text
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter_map(|token| token.strip_prefix("0x").or(token.strip_prefix("0X")))
+            .map(|hex| {
+                u64::from_str_radix(hex.trim_end_matches(['U', 'L', 'u', 'l']), 16)
+                    .map_err(|_| Error::Book(BookError::Keys))
+            })
+            .collect::<Result<Vec<u64>, Error>>()?;
+        Self::new(keys)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::Book(BookError::Io))?;
+        Self::parse(&text)
+    }
+
+    /// The Polyglot hash of `board`. The en passant square only counts when a pawn can actually
+    /// capture there.
+    pub fn key(&self, board: &Board) -> u64 {
+        let mut key = 0;
+        for troop in board
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+        {
+            let kind = troop.piece as usize * 2 + (troop.color == Color::White) as usize;
+            key ^= self.0[64 * kind + troop.position.index() as usize];
+        }
+        let castling = board.castling_rights();
+        let rights = [
+            castling.white_kingside,
+            castling.white_queenside,
+            castling.black_kingside,
+            castling.black_queenside,
+        ];
+        for (index, right) in rights.into_iter().enumerate() {
+            if right {
+                key ^= self.0[CASTLING + index];
+            }
+        }
+        let color = board.side_to_move();
+        if let Some(en_passant) = board.en_passant() {
+            let capturer = [-1, 1].into_iter().any(|files| {
+                en_passant
+                    .offset(files, -color.forward())
+                    .and_then(|position| board.get_square(&position).troop.as_ref())
+                    .is_some_and(|troop| troop.piece == Piece::Pawn && troop.color == color)
+            });
+            if capturer {
+                key ^= self.0[EN_PASSANT + en_passant.file as usize];
+            }
+        }
+        if color == Color::White {
+            key ^= self.0[TURN];
+        }
+        key
+    }
+}
+
+impl Default for PolyglotKeys {
+    /// Polyglot's own table, which published books are hashed with.
+    fn default() -> Self {
+        PolyglotKeys(RANDOM64.to_vec())
+    }
+}
+
+/// A move the book suggests, and how strongly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub m: Move,
+    pub weight: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    key: u64,
+    m: u16,
+    weight: u16,
+}
+
+/// How to pick one of the book's moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The move with the highest weight.
+    Best,
+    /// A random move, each as likely as its share of the total weight.
+    WeightedRandom,
+}
+
+#[derive(Debug, Clone)]
+pub struct Book {
+    keys: PolyglotKeys,
+    entries: Vec<Entry>,
+}
+
+/// Polyglot squares count from a1 = 0 along the ranks, like [`Position::index`].
+fn decode_square(bits: u16) -> Position {
+    Position::from_index((bits & 0o77) as u8)
+}
+
+impl Book {
+    pub fn open(path: impl AsRef<Path>, keys: PolyglotKeys) -> Result<Self, Error> {
+        let bytes = std::fs::read(path).map_err(|_| Error::Book(BookError::Io))?;
+        Self::from_bytes(&bytes, keys)
+    }
+
+    pub fn from_bytes(bytes: &[u8], keys: PolyglotKeys) -> Result<Self, Error> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(Error::Book(BookError::Format));
+        }
+        let mut entries: Vec<Entry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| Entry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                m: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+            })
+            .collect();
+        // Books are written sorted, but lookups rely on it, so don't take that on trust
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Book { keys, entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Turns Polyglot's move encoding into a [`Move`]: to square in the low six bits, then the
+    /// from square, then the promotion piece. Castling is written as the king taking its own
+    /// rook.
+    fn decode(board: &Board, bits: u16) -> Move {
+        let from = decode_square(bits >> 6);
+        let mut to = decode_square(bits);
+        let promotion = match (bits >> 12) & 0b111 {
+            1 => Some(Piece::Knight),
+            2 => Some(Piece::Bishop),
+            3 => Some(Piece::Rook),
+            4 => Some(Piece::Queen),
+            _ => None,
+        };
+        let king = board
+            .get_square(&from)
+            .troop
+            .as_ref()
+            .is_some_and(|troop| troop.piece == Piece::King);
+        // Polyglot writes castling as the king taking its rook, which is how Chess960 boards want it
+        if king
+            && !board.is_chess960()
+            && from.file == File::E
+            && matches!(from.rank, Rank::One | Rank::Eight)
+        {
+            match to.file {
+                File::H => to.file = File::G,
+                File::A => to.file = File::C,
+                _ => {}
+            }
+        }
+        Move {
+            from,
+            to,
+            promotion,
+        }
+    }
+
+    /// Every legal move the book has for `board`, heaviest first.
+    pub fn moves(&self, board: &Board) -> Vec<BookMove> {
+        let key = self.keys.key(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let legal_moves = board.legal_moves();
+        let mut moves: Vec<BookMove> = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .map(|entry| BookMove {
+                m: Self::decode(board, entry.m),
+                weight: entry.weight,
+            })
+            .filter(|book_move| legal_moves.contains(&book_move.m))
+            .collect();
+        moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+        moves
+    }
+
+    /// Picks a book move for `board`, if the book knows the position.
+    pub fn pick(&self, board: &Board, selection: Selection) -> Option<Move> {
+        match selection {
+            Selection::Best => self.moves(board).first().map(|book_move| book_move.m),
+            Selection::WeightedRandom => {
+                let roll = RandomState::new().build_hasher().finish();
+                self.weighted_pick(board, roll)
+            }
+        }
+    }
+
+    /// Picks a book move by weight with a given random number, so the choice can be repeated.
+    /// Moves with weight 0 are only picked when every move has weight 0.
+    pub fn weighted_pick(&self, board: &Board, roll: u64) -> Option<Move> {
+        let moves = self.moves(board);
+        let total: u64 = moves.iter().map(|book_move| book_move.weight as u64).sum();
+        if total == 0 {
+            return moves.first().map(|book_move| book_move.m);
+        }
+        let mut remaining = roll % total;
+        moves.into_iter().find_map(|book_move| {
+            if remaining < book_move.weight as u64 {
+                Some(book_move.m)
+            } else {
+                remaining -= book_move.weight as u64;
+                None
+            }
+        })
+    }
+}
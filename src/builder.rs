@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use crate::{
+    Bitboard, Board, BoardState, CastlingRights, CastlingRooks, Color, Error, File, LastMove,
+    Piece, Pocket, Position, Rank, Square, Troop, Variant,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SetupError {
+    /// A side doesn't have exactly one king when the variant needs one.
+    KingCount(Color),
+    /// Pawns can never stand on the first or eighth rank, except for white's in Horde, which
+    /// start on the first.
+    PawnOnBackRank(Position),
+    /// The side that just moved can't have left its king in check.
+    OpponentInCheck,
+    /// A castling right was given without the king and rook on their starting squares, or with
+    /// the rook on the wrong side of the king.
+    CastlingRights,
+    /// The en passant square doesn't follow from a double pawn push by the side not to move.
+    EnPassant,
+}
+
+/// Sets up arbitrary legal positions, e.g. for puzzles.
+///
+/// ```
+/// # use rust_chess::*;
+/// let board = BoardBuilder::new()
+///     .place(Piece::King, Color::White, "e1".parse().unwrap())
+///     .place(Piece::King, Color::Black, "e8".parse().unwrap())
+///     .place(Piece::Rook, Color::White, "h1".parse().unwrap())
+///     .castling_rights(CastlingRights {
+///         white_kingside: true,
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+/// assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoardBuilder {
+    troops: HashMap<Position, (Piece, Color)>,
+    side_to_move: Color,
+    castling: CastlingRights,
+    en_passant: Option<Position>,
+    castling_rooks: CastlingRooks,
+    chess960: bool,
+    variant: Variant,
+    checks: [u8; 2],
+    pockets: [Pocket; 2],
+    promoted: Bitboard,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        BoardBuilder {
+            troops: HashMap::new(),
+            side_to_move: Color::White,
+            castling: CastlingRights::default(),
+            en_passant: None,
+            castling_rooks: CastlingRooks::default(),
+            chess960: false,
+            variant: Variant::Standard,
+            checks: [0; 2],
+            pockets: [Pocket::default(); 2],
+            promoted: Bitboard::EMPTY,
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Starts from an empty board with white to move and no castling rights.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from an existing position, e.g. to edit it.
+    pub fn from_board(board: &Board) -> Self {
+        let troops = board
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+            .map(|troop| (troop.position, (troop.piece, troop.color)))
+            .collect();
+        BoardBuilder {
+            troops,
+            side_to_move: board.side_to_move(),
+            castling: board.castling,
+            en_passant: board.en_passant,
+            castling_rooks: board.castling_rooks,
+            chess960: board.chess960,
+            variant: board.variant,
+            checks: board.checks,
+            pockets: board.pockets,
+            promoted: board.promoted,
+        }
+    }
+
+    /// Puts a piece on `position`, replacing whatever stood there.
+    pub fn place(mut self, piece: Piece, color: Color, position: Position) -> Self {
+        self.troops.insert(position, (piece, color));
+        self
+    }
+
+    pub fn remove(mut self, position: Position) -> Self {
+        self.troops.remove(&position);
+        self
+    }
+
+    pub fn side_to_move(mut self, color: Color) -> Self {
+        self.side_to_move = color;
+        self
+    }
+
+    pub fn castling_rights(mut self, castling: CastlingRights) -> Self {
+        self.castling = castling;
+        self
+    }
+
+    pub fn en_passant(mut self, en_passant: Option<Position>) -> Self {
+        self.en_passant = en_passant;
+        self
+    }
+
+    /// Sets which files the castling rooks start on, for Chess960 positions.
+    pub fn castling_rooks(mut self, castling_rooks: CastlingRooks) -> Self {
+        self.castling_rooks = castling_rooks;
+        self
+    }
+
+    /// Plays by Chess960 rules, where the king may castle from any file and castling is written as
+    /// the king taking its own rook. Otherwise castling rights need the king on the e-file and
+    /// the rooks in the corners.
+    pub fn chess960(mut self, chess960: bool) -> Self {
+        self.chess960 = chess960;
+        self
+    }
+
+    /// Plays the position by `variant`'s rules.
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Gives `color` the pieces in `pocket` to drop, for Crazyhouse and Bughouse.
+    pub fn pocket(mut self, color: Color, pocket: Pocket) -> Self {
+        self.pockets[color as usize] = pocket;
+        self
+    }
+
+    /// Checks that the position could occur in a game and produces the board.
+    pub fn build(self) -> Result<Board, Error> {
+        let mut white_king: Option<Position> = None;
+        let mut black_king: Option<Position> = None;
+        for (position, (piece, color)) in &self.troops {
+            match piece {
+                Piece::King => {
+                    let king = match color {
+                        Color::White => &mut white_king,
+                        Color::Black => &mut black_king,
+                    };
+                    if king.is_some() && self.variant.needs_king(*color) {
+                        return Err(Error::Setup(SetupError::KingCount(*color)));
+                    }
+                    // Of several kings, track the one nearest a1, whatever order they come in
+                    if king.is_none_or(|king| position.index() < king.index()) {
+                        *king = Some(*position);
+                    }
+                }
+                Piece::Pawn
+                    if position.rank == Rank::Eight
+                        || (position.rank == Rank::One
+                            && (self.variant != Variant::Horde || *color == Color::Black)) =>
+                {
+                    return Err(Error::Setup(SetupError::PawnOnBackRank(*position)));
+                }
+                _ => {}
+            }
+        }
+        for (color, king) in [(Color::White, white_king), (Color::Black, black_king)] {
+            if king.is_none() && self.variant.needs_king(color) {
+                return Err(Error::Setup(SetupError::KingCount(color)));
+            }
+        }
+
+        let has = |piece: Piece, color: Color, file: File, rank: Rank| {
+            self.troops.get(&Position { file, rank }) == Some(&(piece, color))
+        };
+        for (allowed, color, rank, king, rook_file, kingside) in [
+            (
+                self.castling.white_kingside,
+                Color::White,
+                Rank::One,
+                white_king,
+                self.castling_rooks.white_kingside,
+                true,
+            ),
+            (
+                self.castling.white_queenside,
+                Color::White,
+                Rank::One,
+                white_king,
+                self.castling_rooks.white_queenside,
+                false,
+            ),
+            (
+                self.castling.black_kingside,
+                Color::Black,
+                Rank::Eight,
+                black_king,
+                self.castling_rooks.black_kingside,
+                true,
+            ),
+            (
+                self.castling.black_queenside,
+                Color::Black,
+                Rank::Eight,
+                black_king,
+                self.castling_rooks.black_queenside,
+                false,
+            ),
+        ] {
+            if !allowed {
+                continue;
+            }
+            let Some(king) = king else {
+                return Err(Error::Setup(SetupError::CastlingRights));
+            };
+            let standard = king.file == File::E
+                && rook_file
+                    == match kingside {
+                        true => File::H,
+                        false => File::A,
+                    };
+            let rook_side = match kingside {
+                true => rook_file as u8 > king.file as u8,
+                false => (rook_file as u8) < king.file as u8,
+            };
+            if king.rank != rank
+                || !has(Piece::Rook, color, rook_file, rank)
+                || !rook_side
+                || !(standard || self.chess960)
+            {
+                return Err(Error::Setup(SetupError::CastlingRights));
+            }
+        }
+
+        if let Some(en_passant) = self.en_passant {
+            // The pawn that just moved two squares stands in front of the en passant square
+            let (target_rank, pawn_rank, origin_rank) = match self.side_to_move {
+                Color::White => (Rank::Six, Rank::Five, Rank::Seven),
+                Color::Black => (Rank::Three, Rank::Four, Rank::Two),
+            };
+            let mover = self.side_to_move.opponent();
+            if en_passant.rank != target_rank
+                || !has(Piece::Pawn, mover, en_passant.file, pawn_rank)
+                || self.troops.contains_key(&en_passant)
+                || self.troops.contains_key(&Position {
+                    file: en_passant.file,
+                    rank: origin_rank,
+                })
+            {
+                return Err(Error::Setup(SetupError::EnPassant));
+            }
+        }
+
+        let mut squares: HashMap<File, HashMap<Rank, Square>> = HashMap::new();
+        for file in 1..=8 {
+            for rank in 1..=8 {
+                let position = Position {
+                    file: File::try_from(file).unwrap(),
+                    rank: Rank::try_from(rank).unwrap(),
+                };
+                let troop = self.troops.get(&position).map(|(piece, color)| Troop {
+                    piece: *piece,
+                    color: *color,
+                    position,
+                });
+                squares
+                    .entry(position.file)
+                    .or_default()
+                    .insert(position.rank, Square { troop, position });
+            }
+        }
+
+        let mut board = Board {
+            squares,
+            state: BoardState::ToMove(self.side_to_move),
+            turn: self.side_to_move,
+            black_king,
+            white_king,
+            last_move: LastMove {
+                from: Position {
+                    file: File::A,
+                    rank: Rank::One,
+                },
+                to: Position {
+                    file: File::A,
+                    rank: Rank::One,
+                },
+                promotion: None,
+            },
+            castling: self.castling,
+            en_passant: self.en_passant,
+            castling_rooks: self.castling_rooks,
+            chess960: self.chess960,
+            variant: self.variant,
+            checks: self.checks,
+            pockets: self.pockets,
+            // Only pieces still on the board can have been promoted
+            promoted: self.troops.keys().copied().collect::<Bitboard>() & self.promoted,
+        };
+        if board.check_for_check(self.side_to_move.opponent()) {
+            return Err(Error::Setup(SetupError::OpponentInCheck));
+        }
+        board.update_state(self.side_to_move);
+        Ok(board)
+    }
+}
@@ -0,0 +1,89 @@
+//! Chess960 starting positions, numbered 0 to 959 as in Scharnagl's scheme, where 518 is the
+//! standard setup.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::Board;
+
+/// Where the two knights go among the five squares left after the bishops and queen.
+const KNIGHTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+/// White's back rank for starting position `index`, from the a-file to the h-file.
+fn back_rank(index: u16) -> [char; 8] {
+    let mut rank = [' '; 8];
+    let mut index = index as usize;
+    // The light-squared bishop goes on b, d, f or h and the dark-squared one on a, c, e or g
+    rank[index % 4 * 2 + 1] = 'B';
+    index /= 4;
+    rank[index % 4 * 2] = 'B';
+    index /= 4;
+
+    let place = |rank: &mut [char; 8], nth: usize, piece: char| {
+        let file = (0..8).filter(|file| rank[*file] == ' ').nth(nth).unwrap();
+        rank[file] = piece;
+    };
+    place(&mut rank, index % 6, 'Q');
+    index /= 6;
+    let (first, second) = KNIGHTS[index];
+    // The later knight goes first so the earlier one's count of empty squares still holds
+    place(&mut rank, second, 'N');
+    place(&mut rank, first, 'N');
+
+    // The king goes between the rooks on the three squares left
+    for piece in ['R', 'K', 'R'] {
+        place(&mut rank, 0, piece);
+    }
+    rank
+}
+
+impl Board {
+    /// Chess960 starting position number `index`, or `None` past 959. Castling is written as the
+    /// king taking its own rook, see [`Board::is_chess960`].
+    ///
+    /// ```
+    /// # use rust_chess::Board;
+    /// let board = Board::chess960(518).unwrap();
+    /// assert_eq!(
+    ///     board.to_fen(),
+    ///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    /// );
+    /// ```
+    pub fn chess960(index: u16) -> Option<Board> {
+        if index > 959 {
+            return None;
+        }
+        let white: String = back_rank(index).iter().collect();
+        let castling: String = white
+            .char_indices()
+            .filter(|(_, piece)| *piece == 'R')
+            .map(|(file, _)| (b'A' + file as u8) as char)
+            .rev()
+            .collect();
+        let fen = format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w {}{} - 0 1",
+            white.to_lowercase(),
+            white,
+            castling,
+            castling.to_lowercase()
+        );
+        Board::from_fen(&fen).ok()
+    }
+
+    /// One of the 960 starting positions, picked at random.
+    pub fn random_chess960() -> Board {
+        let roll = RandomState::new().build_hasher().finish();
+        Board::chess960((roll % 960) as u16).unwrap()
+    }
+}
@@ -0,0 +1,299 @@
+//! A terminal client for playing a game by typing moves, against another person or against the
+//! engine.
+//!
+//! [`run`] reads a move or command per line. Moves can be written in SAN (`Nf3`, `O-O`) or as
+//! coordinates (`g1f3`), and the board is shown again after every move with the last one
+//! marked, drawn however [`Options::renderer`] says.
+
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::engine::{Engine, Limits, Score};
+use crate::render::{Glyphs, Renderer};
+use crate::{pgn, Board, BoardState, Color, Move};
+
+const HELP: &str = "Type a move like Nf3, O-O or g1f3, or one of these commands:
+  undo          take back the last move
+  resign        give up the game
+  draw          offer a draw, or accept one
+  save <file>   write the game to a PGN file
+  board         show the board again
+  help          show this message
+  quit          leave";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The side the engine plays, or `None` for two people taking turns.
+    pub engine: Option<Color>,
+    /// How long the engine thinks about each move.
+    pub limits: Limits,
+    pub renderer: Renderer,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            engine: None,
+            limits: Limits::time(Duration::from_secs(2)),
+            renderer: Renderer {
+                glyphs: Glyphs::Ascii,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// How a game ended other than on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Resigned(Color),
+    DrawAgreed,
+}
+
+fn name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+struct Client<W: Write> {
+    start: Board,
+    board: Board,
+    moves: Vec<Move>,
+    /// The positions before each move, for `undo`.
+    history: Vec<Board>,
+    outcome: Option<Outcome>,
+    /// The side that offered a draw on its last turn.
+    draw_offer: Option<Color>,
+    engine: Engine,
+    options: Options,
+    output: W,
+}
+
+impl<W: Write> Client<W> {
+    fn say(&mut self, text: &str) -> io::Result<()> {
+        writeln!(self.output, "{}", text)
+    }
+
+    fn result(&self) -> Option<(&'static str, String)> {
+        match (self.outcome, &self.board.state) {
+            (Some(Outcome::Resigned(color)), _) => Some((
+                match color {
+                    Color::White => "0-1",
+                    Color::Black => "1-0",
+                },
+                format!("{} resigns", name(color)),
+            )),
+            (Some(Outcome::DrawAgreed), _) => Some(("1/2-1/2", "Draw agreed".to_string())),
+            (None, BoardState::Checkmate(Color::Black)) => Some(("1-0", "White mates".to_string())),
+            (None, BoardState::Checkmate(Color::White)) => Some(("0-1", "Black mates".to_string())),
+            (None, BoardState::Stalemate) => Some(("1/2-1/2", "Stalemate".to_string())),
+            (None, BoardState::Draw) => Some(("1/2-1/2", "Draw".to_string())),
+            (
+                None,
+                BoardState::VariantLoss(Color::Black) | BoardState::VariantWin(Color::White),
+            ) => Some((
+                "1-0",
+                format!("White wins by {} rules", self.board.variant().name()),
+            )),
+            (
+                None,
+                BoardState::VariantLoss(Color::White) | BoardState::VariantWin(Color::Black),
+            ) => Some((
+                "0-1",
+                format!("Black wins by {} rules", self.board.variant().name()),
+            )),
+            (None, BoardState::ToMove(_) | BoardState::Check(_)) => None,
+        }
+    }
+
+    /// Shows the board and whose turn it is, or how the game ended.
+    fn show(&mut self) -> io::Result<()> {
+        let board = self.options.renderer.render(&self.board);
+        self.say(&board)?;
+        let status = match self.result() {
+            Some((result, reason)) => format!("{} ({})", result, reason),
+            None => {
+                let color = self.board.side_to_move();
+                match self.board.state {
+                    BoardState::Check(_) => format!("{} to move, in check", name(color)),
+                    _ => format!("{} to move", name(color)),
+                }
+            }
+        };
+        self.say(&status)
+    }
+
+    fn engine_to_move(&self) -> bool {
+        self.result().is_none() && self.options.engine == Some(self.board.side_to_move())
+    }
+
+    fn play(&mut self, m: Move) {
+        self.history.push(self.board.clone());
+        self.board.make_move(m).unwrap();
+        self.moves.push(m);
+        // Making a move turns down any draw the opponent offered
+        if self.draw_offer != Some(self.board.side_to_move().opponent()) {
+            self.draw_offer = None;
+        }
+    }
+
+    fn engine_move(&mut self) -> io::Result<()> {
+        let Ok(result) = self.engine.search(&self.board, self.options.limits) else {
+            return self.say("rust-chess can't play this variant.");
+        };
+        if let Some(m) = result.best_move {
+            let san = self.board.san(m).unwrap();
+            self.play(m);
+            self.say(&format!("rust-chess plays {}", san))?;
+        }
+        self.show()
+    }
+
+    fn user_move(&mut self, text: &str) -> io::Result<()> {
+        if self.result().is_some() {
+            return self.say("The game is over. Type undo to take back a move, or quit.");
+        }
+        let m = text
+            .parse::<Move>()
+            .ok()
+            .filter(|m| self.board.legal_moves().contains(m))
+            .or_else(|| self.board.parse_san(text).ok());
+        let Some(m) = m else {
+            return self.say(&format!("Illegal move: {}", text));
+        };
+        self.play(m);
+        self.show()?;
+        if self.engine_to_move() {
+            self.engine_move()?;
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self) -> io::Result<()> {
+        if self.outcome.take().is_some() {
+            return self.show();
+        }
+        let Some(board) = self.history.pop() else {
+            return self.say("There's nothing to take back.");
+        };
+        self.board = board;
+        self.moves.pop();
+        // Against the engine take back its reply too, so it's the player's turn again
+        if self.engine_to_move() && !self.history.is_empty() {
+            self.board = self.history.pop().unwrap();
+            self.moves.pop();
+        }
+        self.draw_offer = None;
+        self.show()?;
+        if self.engine_to_move() {
+            self.engine_move()?;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        if self.result().is_some() {
+            return self.say("The game is over.");
+        }
+        let color = self.board.side_to_move();
+        if self.draw_offer == Some(color.opponent()) {
+            self.outcome = Some(Outcome::DrawAgreed);
+            return self.show();
+        }
+        if self.options.engine == Some(color.opponent()) {
+            // The engine takes a draw unless it thinks it's better
+            let result = self.engine.search(&self.board, self.options.limits);
+            let ahead = match result.map(|result| result.score) {
+                Ok(Score::Centipawns(centipawns)) => centipawns < 0,
+                Ok(Score::Mate(moves)) => moves < 0,
+                Err(_) => false,
+            };
+            if ahead {
+                return self.say("rust-chess declines the draw.");
+            }
+            self.outcome = Some(Outcome::DrawAgreed);
+            return self.show();
+        }
+        self.draw_offer = Some(color);
+        self.say(&format!(
+            "{} offers a draw. {} can type draw to accept, or move to decline.",
+            name(color),
+            name(color.opponent())
+        ))
+    }
+
+    fn save(&mut self, path: &str) -> io::Result<()> {
+        let player = |color| match self.options.engine == Some(color) {
+            true => "rust-chess",
+            false => "Player",
+        };
+        let result = self.result().map_or("*", |(result, _)| result);
+        let pgn = pgn::game(
+            &self.start,
+            &self.moves,
+            result,
+            &[
+                ("Event", "Casual game"),
+                ("White", player(Color::White)),
+                ("Black", player(Color::Black)),
+            ],
+        )
+        .unwrap();
+        match std::fs::write(path, pgn) {
+            Ok(_) => self.say(&format!("Saved to {}", path)),
+            Err(err) => self.say(&format!("Couldn't save to {}: {}", path, err)),
+        }
+    }
+
+    /// Handles one line, returning whether to keep going.
+    fn command(&mut self, line: &str) -> io::Result<bool> {
+        let line = line.trim();
+        match line.split_once(' ').unwrap_or((line, "")) {
+            ("", _) => {}
+            ("quit" | "exit", _) => return Ok(false),
+            ("help", _) => self.say(HELP)?,
+            ("board", _) => self.show()?,
+            ("undo", _) => self.undo()?,
+            ("resign", _) => match self.result() {
+                Some(_) => self.say("The game is over.")?,
+                None => {
+                    self.outcome = Some(Outcome::Resigned(self.board.side_to_move()));
+                    self.show()?;
+                }
+            },
+            ("draw", _) => self.draw()?,
+            ("save", "") => self.say("Usage: save <file>")?,
+            ("save", path) => self.save(path.trim())?,
+            _ => self.user_move(line)?,
+        }
+        Ok(true)
+    }
+}
+
+/// Plays a game from the start position until `quit` or the end of `input`.
+pub fn run(input: impl BufRead, output: impl Write, options: Options) -> io::Result<()> {
+    let mut client = Client {
+        start: Board::default(),
+        board: Board::default(),
+        moves: vec![],
+        history: vec![],
+        outcome: None,
+        draw_offer: None,
+        engine: Engine::default(),
+        options,
+        output,
+    };
+    client.say("Type help for the commands.")?;
+    client.show()?;
+    if client.engine_to_move() {
+        client.engine_move()?;
+    }
+    for line in input.lines() {
+        if !client.command(&line?)? {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,252 @@
+//! Pockets and drop moves for Crazyhouse and Bughouse, where captured pieces change sides and
+//! can be put back on the board instead of moving.
+
+use crate::{Board, Color, Piece, Position};
+#[cfg(feature = "actions")]
+use crate::{BoardState, Error, Move, MoveError, Rank, Square, Troop, Variant};
+
+/// The pieces a side has captured and may drop. Kings are never captured, so they never end up
+/// here.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pocket {
+    /// How many of each piece, indexed by [`Piece`] from pawns to queens.
+    counts: [u8; 5],
+}
+
+impl Pocket {
+    /// The pieces a pocket can hold, in the order FEN lists them.
+    pub const PIECES: [Piece; 5] = [
+        Piece::Queen,
+        Piece::Rook,
+        Piece::Bishop,
+        Piece::Knight,
+        Piece::Pawn,
+    ];
+
+    pub fn count(&self, piece: Piece) -> u8 {
+        match piece {
+            Piece::King => 0,
+            _ => self.counts[piece as usize],
+        }
+    }
+
+    pub fn add(&mut self, piece: Piece) {
+        if piece != Piece::King {
+            self.counts[piece as usize] += 1;
+        }
+    }
+
+    /// Takes out one `piece`, returning whether there was one.
+    pub fn take(&mut self, piece: Piece) -> bool {
+        if self.count(piece) == 0 {
+            return false;
+        }
+        self.counts[piece as usize] -= 1;
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+}
+
+/// Putting a piece from the pocket on an empty square, written like `N@f3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropMove {
+    pub piece: Piece,
+    pub to: Position,
+}
+
+impl std::fmt::Display for DropMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.piece.to_fen_char(Color::White), self.to)
+    }
+}
+
+/// Reads `N@f3`, and pawn drops with or without the `P`, like `@e4`.
+impl std::str::FromStr for DropMove {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((piece, to)) = s.split_once('@') else {
+            return Err(crate::Error::FileParse);
+        };
+        let piece = match piece.chars().collect::<Vec<_>>()[..] {
+            [] => Piece::Pawn,
+            [c] => match Piece::from_fen_char(c) {
+                Some((piece, Color::White)) if piece != Piece::King => piece,
+                _ => return Err(crate::Error::FileParse),
+            },
+            _ => return Err(crate::Error::FileParse),
+        };
+        Ok(DropMove {
+            piece,
+            to: to.parse()?,
+        })
+    }
+}
+
+impl Board {
+    /// The pieces `color` has in hand. Always empty outside Crazyhouse and Bughouse.
+    pub fn pocket(&self, color: Color) -> Pocket {
+        self.pockets[color as usize]
+    }
+
+    /// Whether the piece on `position` got there by promotion, and so goes back into a pocket
+    /// as a pawn when captured.
+    pub fn is_promoted(&self, position: Position) -> bool {
+        self.promoted.contains(position)
+    }
+}
+
+#[cfg(feature = "actions")]
+impl Board {
+    /// Keeps the pockets and promoted pieces up to date for a move from `from` to `to`, before
+    /// the pieces move. `captured` is where the captured piece stands, if there is one.
+    pub(crate) fn pocket_capture(&mut self, m: Move, captured: Option<Position>) {
+        if !self.drops() {
+            return;
+        }
+        if let Some(position) = captured {
+            let troop = self.get_square(&position).troop.clone().unwrap();
+            let piece = match self.promoted.contains(position) {
+                true => Piece::Pawn,
+                false => troop.piece,
+            };
+            self.pockets[troop.color.opponent() as usize].add(piece);
+            self.promoted.remove(position);
+        }
+        if self.promoted.contains(m.from) || m.promotion.is_some() {
+            self.promoted.remove(m.from);
+            self.promoted.insert(m.to);
+        }
+    }
+
+    /// Every drop the side to move can legally make.
+    pub fn legal_drops(&self) -> Vec<DropMove> {
+        let color = match self.state {
+            BoardState::ToMove(color) | BoardState::Check(color) => color,
+            _ => return vec![],
+        };
+        let pocket = self.pocket(color);
+        if pocket.is_empty() {
+            return vec![];
+        }
+        let in_check = self.check_for_check(color);
+        let mut drops = vec![];
+        let empty = self
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter(|square| square.troop.is_none());
+        for Square { position, .. } in empty {
+            // Dropping can't expose the king, but in check it has to block
+            if in_check {
+                let mut board = self.clone();
+                board.get_mut_square(position).troop = Some(Troop {
+                    piece: Piece::Knight,
+                    color,
+                    position: *position,
+                });
+                if board.check_for_check(color) {
+                    continue;
+                }
+            }
+            for piece in Pocket::PIECES {
+                if pocket.count(piece) == 0
+                    || (piece == Piece::Pawn && matches!(position.rank, Rank::One | Rank::Eight))
+                {
+                    continue;
+                }
+                drops.push(DropMove {
+                    piece,
+                    to: *position,
+                });
+            }
+        }
+        drops.sort_by_key(|drop| (drop.to, drop.piece as u8));
+        drops
+    }
+
+    /// Drops a piece from the side to move's pocket.
+    pub fn make_drop(&mut self, drop: DropMove) -> Result<(), Error> {
+        let color = match self.state {
+            BoardState::ToMove(color) | BoardState::Check(color) => color,
+            _ => return Err(Error::Move(MoveError::GameOver)),
+        };
+        if !self.legal_drops().contains(&drop) {
+            return Err(Error::Move(MoveError::Other));
+        }
+        self.pockets[color as usize].take(drop.piece);
+        self.get_mut_square(&drop.to).troop = Some(Troop {
+            piece: drop.piece,
+            color,
+            position: drop.to,
+        });
+        self.last_move = crate::LastMove {
+            from: drop.to,
+            to: drop.to,
+            promotion: None,
+        };
+        self.en_passant = None;
+        self.count_check(color);
+        self.update_state(color.opponent());
+        Ok(())
+    }
+}
+
+/// Two boards played by two teams of two. Partners play opposite colors, and whatever one of
+/// them captures goes into the other's pocket, to drop on their own board.
+#[cfg(feature = "actions")]
+#[derive(Debug, Clone)]
+pub struct Bughouse {
+    pub boards: [Board; 2],
+}
+
+#[cfg(feature = "actions")]
+impl Default for Bughouse {
+    fn default() -> Self {
+        Bughouse {
+            boards: [
+                Variant::Bughouse.starting_position(),
+                Variant::Bughouse.starting_position(),
+            ],
+        }
+    }
+}
+
+#[cfg(feature = "actions")]
+impl Bughouse {
+    /// Plays `m` on board `board`, 0 or 1, handing anything it captures to the partner.
+    pub fn make_move(&mut self, board: usize, m: Move) -> Result<(), Error> {
+        let before = [
+            self.boards[board].pocket(Color::White),
+            self.boards[board].pocket(Color::Black),
+        ];
+        self.boards[board].make_move(m)?;
+        for (index, color) in [Color::White, Color::Black].into_iter().enumerate() {
+            for piece in Pocket::PIECES {
+                let gained =
+                    self.boards[board].pocket(color).count(piece) - before[index].count(piece);
+                for _ in 0..gained {
+                    self.boards[board].pockets[color as usize].take(piece);
+                    self.boards[1 - board].pockets[color.opponent() as usize].add(piece);
+                }
+            }
+        }
+        let partner = &mut self.boards[1 - board];
+        if let BoardState::ToMove(_) | BoardState::Check(_) | BoardState::Checkmate(_) =
+            partner.state
+        {
+            // A new piece in hand can be the block that gets out of a mate
+            partner.update_state(partner.side_to_move());
+        }
+        Ok(())
+    }
+
+    /// Drops a piece on board `board`, 0 or 1.
+    pub fn make_drop(&mut self, board: usize, drop: DropMove) -> Result<(), Error> {
+        self.boards[board].make_drop(drop)
+    }
+}
@@ -0,0 +1,589 @@
+//! A built-in opponent: iterative-deepening negamax search with alpha-beta pruning, and a
+//! quiescence search over captures so it doesn't stop counting material mid-exchange.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::eval::{evaluate, EvalParams};
+use crate::variant::Variant;
+use crate::{Board, BoardState, Error, Move};
+
+mod ordering;
+mod tt;
+pub use ordering::static_exchange;
+use ordering::Heuristics;
+pub use tt::{Bound, Entry, TranspositionTable};
+
+pub const MAX_DEPTH: u32 = 64;
+/// The score for delivering mate right now. Mates further away score a little less, so the
+/// search prefers the quickest mate and the slowest way of getting mated.
+const MATE: i32 = 100_000;
+/// Scores beyond this are mates, counting the plies quiescence search can add.
+const MATE_BOUND: i32 = MATE - 2 * MAX_DEPTH as i32;
+const INFINITY: i32 = 1_000_000;
+/// The transposition table size [`search`] uses.
+pub const DEFAULT_HASH_MB: usize = 16;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// The search doesn't know the variant's moves. It can't play drops, so Crazyhouse and
+    /// Bughouse positions would look like mate whenever only a drop gets out of check.
+    UnsupportedVariant(Variant),
+}
+
+/// When to stop searching. Whichever limit is reached first ends the search, and without any
+/// limits it goes all the way to [`MAX_DEPTH`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl Limits {
+    pub fn depth(depth: u32) -> Self {
+        Limits {
+            depth: Some(depth),
+            ..Default::default()
+        }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        Limits {
+            nodes: Some(nodes),
+            ..Default::default()
+        }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        Limits {
+            time: Some(time),
+            ..Default::default()
+        }
+    }
+
+    /// A time limit for playing on a clock with `remaining` time left, getting `increment` back
+    /// after every move and `moves_to_go` moves left until the next time control. Without a
+    /// control in sight it plans for 30 more moves.
+    pub fn clock(remaining: Duration, increment: Duration, moves_to_go: Option<u32>) -> Self {
+        let budget = remaining / moves_to_go.unwrap_or(30).max(1) + increment * 3 / 4;
+        // Keep something back for the time it takes to get the move to the GUI
+        let margin = Duration::from_millis(50);
+        Self::time(
+            budget
+                .min(remaining.saturating_sub(margin))
+                .max(Duration::from_millis(1)),
+        )
+    }
+}
+
+/// How good the position is for the side to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    /// Mate in this many moves, or getting mated if it's negative.
+    Mate(i32),
+}
+
+impl Score {
+    fn from_internal(score: i32) -> Score {
+        if score.abs() >= MATE - MAX_DEPTH as i32 {
+            let plies = MATE - score.abs();
+            let moves = (plies + 1) / 2;
+            Score::Mate(if score > 0 { moves } else { -moves })
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "cp {}", centipawns),
+            Score::Mate(moves) => write!(f, "mate {}", moves),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// `None` if the side to move has no legal moves.
+    pub best_move: Option<Move>,
+    pub score: Score,
+    /// The line the search expects, starting with `best_move`.
+    pub pv: Vec<Move>,
+    /// The deepest iteration that finished.
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+/// One of the lines [`Engine::analyze_with`] finds, best first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalysisLine {
+    pub m: Move,
+    pub score: Score,
+    /// The line the search expects after `m`, starting with it.
+    pub pv: Vec<Move>,
+    /// `pv` in Standard Algebraic Notation.
+    pub san: Vec<String>,
+    pub depth: u32,
+}
+
+/// Positions for comparing changes to the search by node count: the start position, three
+/// tactical middlegames and a rook endgame.
+pub const BENCH_POSITIONS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+];
+
+/// Searches each of [`BENCH_POSITIONS`] to `depth` with a fresh engine and adds up the nodes.
+/// The count only changes when the search does, so it shows what a change costs or saves.
+pub fn bench(depth: u32) -> u64 {
+    BENCH_POSITIONS
+        .iter()
+        .map(|fen| {
+            let board = Board::from_fen(fen).unwrap();
+            let result = Engine::default()
+                .search(&board, Limits::depth(depth))
+                .unwrap();
+            result.nodes
+        })
+        .sum()
+}
+
+/// Looks for the best move for the side to move, with the default evaluation and a fresh
+/// transposition table.
+pub fn search(board: &Board, limits: Limits) -> Result<SearchResult, Error> {
+    Engine::default().search(board, limits)
+}
+
+/// Finds the `multipv` best moves for the side to move, searching each `depth` plies deep,
+/// with the default evaluation and a fresh transposition table.
+pub fn analyze(board: &Board, depth: u32, multipv: usize) -> Result<Vec<AnalysisLine>, Error> {
+    Engine::default().analyze_with(board, depth, multipv, &AtomicBool::new(false), |_| {})
+}
+
+/// A search engine that keeps its transposition table between searches, so each search starts
+/// from what earlier ones learned about the same positions.
+#[derive(Debug, Clone)]
+pub struct Engine {
+    pub params: EvalParams,
+    pub table: TranspositionTable,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new(DEFAULT_HASH_MB)
+    }
+}
+
+impl Engine {
+    /// An engine with the default evaluation and a transposition table of about `hash_mb`
+    /// megabytes.
+    pub fn new(hash_mb: usize) -> Self {
+        Engine {
+            params: EvalParams::default(),
+            table: TranspositionTable::new(hash_mb),
+        }
+    }
+
+    /// Looks for the best move for the side to move. Variants with drops can't be searched.
+    pub fn search(&mut self, board: &Board, limits: Limits) -> Result<SearchResult, Error> {
+        self.search_with(board, limits, &AtomicBool::new(false), |_| {})
+    }
+
+    /// Like [`Engine::search`], but also stopping as soon as another thread sets `stop`, and
+    /// calling `report` with the result of every iteration that finishes.
+    pub fn search_with(
+        &mut self,
+        board: &Board,
+        limits: Limits,
+        stop: &AtomicBool,
+        mut report: impl FnMut(&SearchResult),
+    ) -> Result<SearchResult, Error> {
+        supported(board)?;
+        self.table.new_search();
+        let result = Searcher {
+            limits,
+            params: &self.params,
+            table: &mut self.table,
+            heuristics: Heuristics::new(),
+            stop,
+            report: &mut report,
+            excluded: vec![],
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+        }
+        .iterative_deepening(board);
+        Ok(result)
+    }
+
+    /// Finds the `multipv` best moves for the side to move, or as many as there are, each with
+    /// its score and line. Each depth up to `depth` is searched in turn, and `report` gets the
+    /// lines every time one finishes. If another thread sets `stop`, the lines from the deepest
+    /// finished depth are returned, which may be none at all.
+    pub fn analyze_with(
+        &mut self,
+        board: &Board,
+        depth: u32,
+        multipv: usize,
+        stop: &AtomicBool,
+        mut report: impl FnMut(&[AnalysisLine]),
+    ) -> Result<Vec<AnalysisLine>, Error> {
+        supported(board)?;
+        self.table.new_search();
+        let count = multipv.min(board.legal_moves().len());
+        if count == 0 {
+            return Ok(vec![]);
+        }
+        let mut searcher = Searcher {
+            limits: Limits::depth(depth),
+            params: &self.params,
+            table: &mut self.table,
+            heuristics: Heuristics::new(),
+            stop,
+            report: &mut |_| {},
+            excluded: vec![],
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+        };
+        let mut lines = vec![];
+        for depth in 1..=depth.min(MAX_DEPTH) {
+            // Each line is the best move the earlier lines at this depth haven't taken
+            searcher.excluded.clear();
+            let mut found = vec![];
+            while found.len() < count {
+                let mut pv = vec![];
+                let score = searcher.negamax(board, depth, 0, -INFINITY, INFINITY, &mut pv);
+                if searcher.stopped {
+                    return Ok(lines);
+                }
+                searcher.excluded.push(pv[0]);
+                found.push((score, pv));
+            }
+            found.sort_by_key(|(score, _)| -score);
+            let resolved = found
+                .iter()
+                .all(|(score, _)| score.abs() >= MATE - depth as i32);
+            lines = found
+                .into_iter()
+                .map(|(score, pv)| AnalysisLine {
+                    m: pv[0],
+                    score: Score::from_internal(score),
+                    san: board.san_line(&pv).unwrap(),
+                    pv,
+                    depth,
+                })
+                .collect();
+            report(&lines);
+            // Every line ends in mate, so searching deeper won't change them
+            if resolved {
+                break;
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Refuses positions the search can't play properly.
+fn supported(board: &Board) -> Result<(), Error> {
+    match board.drops() {
+        true => Err(Error::Engine(EngineError::UnsupportedVariant(
+            board.variant(),
+        ))),
+        false => Ok(()),
+    }
+}
+
+/// Mate scores count plies from the root, but the table is shared between nodes at different
+/// plies, so it stores them counting from the node instead.
+fn score_to_table(score: i32, ply: u32) -> i32 {
+    if score >= MATE_BOUND {
+        score + ply as i32
+    } else if score <= -MATE_BOUND {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: u32) -> i32 {
+    if score >= MATE_BOUND {
+        score - ply as i32
+    } else if score <= -MATE_BOUND {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+/// Plays an already legal move. Unlike [`Board::make_move`] this doesn't look for checkmate or
+/// stalemate, since the search finds out by generating moves anyway.
+fn play(board: &Board, m: Move) -> Board {
+    let color = board.side_to_move().opponent();
+    let mut child = board.clone();
+    child.apply_move(m);
+    child.turn = color;
+    child.state = match child.check_for_check(color) {
+        true => BoardState::Check(color),
+        false => BoardState::ToMove(color),
+    };
+    if let Some(state) = child.variant_outcome(color) {
+        child.state = state;
+    }
+    child
+}
+
+struct Searcher<'a> {
+    limits: Limits,
+    params: &'a EvalParams,
+    table: &'a mut TranspositionTable,
+    heuristics: Heuristics,
+    stop: &'a AtomicBool,
+    report: &'a mut dyn FnMut(&SearchResult),
+    /// Root moves to leave out, for finding the next best line in multi-PV analysis.
+    excluded: Vec<Move>,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+}
+
+impl Searcher<'_> {
+    fn out_of_budget(&mut self) -> bool {
+        if let Some(nodes) = self.limits.nodes {
+            if self.nodes >= nodes {
+                self.stopped = true;
+            }
+        }
+        if let Some(time) = self.limits.time {
+            if self.start.elapsed() >= time {
+                self.stopped = true;
+            }
+        }
+        if self.stop.load(Ordering::Relaxed) {
+            self.stopped = true;
+        }
+        self.stopped
+    }
+
+    fn iterative_deepening(&mut self, board: &Board) -> SearchResult {
+        let mut result = SearchResult {
+            best_move: None,
+            score: Score::Centipawns(0),
+            pv: vec![],
+            depth: 0,
+            nodes: 0,
+        };
+        let max_depth = self.limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+        for depth in 1..=max_depth {
+            let mut pv = vec![];
+            let score = self.negamax(board, depth, 0, -INFINITY, INFINITY, &mut pv);
+            // An unfinished iteration is only better than nothing
+            if self.stopped && result.best_move.is_some() {
+                break;
+            }
+            result.best_move = pv.first().copied();
+            result.score = Score::from_internal(score);
+            result.pv = pv;
+            result.depth = depth;
+            result.nodes = self.nodes;
+            if !self.stopped {
+                (self.report)(&result);
+            }
+            // Once a mate fits within the full-width search there's no shorter one to find
+            if self.stopped || (score.abs() >= MATE - depth as i32) {
+                break;
+            }
+        }
+        // Stopped before the first iteration got anywhere, but any legal move beats none
+        if result.best_move.is_none() {
+            result.best_move = board.legal_moves().first().copied();
+            result.pv = result.best_move.into_iter().collect();
+        }
+        result.nodes = self.nodes;
+        result
+    }
+
+    /// Follows the best moves stored in the table from `board`, for as long as they're legal.
+    fn table_line(&self, board: &Board, length: u32) -> Vec<Move> {
+        let mut line = vec![];
+        let mut board = board.clone();
+        while line.len() < length as usize {
+            let Some(m) = self
+                .table
+                .probe(board.zobrist_hash())
+                .and_then(|entry| entry.best_move)
+            else {
+                break;
+            };
+            if !board.legal_moves().contains(&m) {
+                break;
+            }
+            board = play(&board, m);
+            line.push(m);
+        }
+        line
+    }
+
+    /// Searches `board` to `depth` plies, filling `pv` with the best line found.
+    fn negamax(
+        &mut self,
+        board: &Board,
+        depth: u32,
+        ply: u32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.out_of_budget() {
+            return 0;
+        }
+
+        let mut moves = board.legal_moves();
+        if moves.is_empty() {
+            return match board.state {
+                BoardState::Check(_) | BoardState::VariantLoss(_) => -MATE + ply as i32,
+                BoardState::VariantWin(_) => MATE - ply as i32,
+                _ => 0,
+            };
+        }
+        if depth == 0 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        let key = board.zobrist_hash();
+        let entry = self.table.probe(key).copied();
+        if let Some(entry) = entry {
+            // The root always searches, so there's a move to report
+            if ply > 0 && entry.depth >= depth {
+                let score = score_from_table(entry.score, ply);
+                let cutoff = match entry.bound {
+                    Bound::Exact => Some(score.clamp(alpha, beta)),
+                    Bound::Lower if score >= beta => Some(beta),
+                    Bound::Upper if score <= alpha => Some(alpha),
+                    _ => None,
+                };
+                if let Some(score) = cutoff {
+                    pv.clear();
+                    if entry.bound == Bound::Exact {
+                        pv.extend(self.table_line(board, depth));
+                    }
+                    return score;
+                }
+            }
+        }
+        self.heuristics.order(
+            board,
+            &mut moves,
+            entry.and_then(|entry| entry.best_move),
+            ply,
+        );
+        let excluding = ply == 0 && !self.excluded.is_empty();
+        if excluding {
+            moves.retain(|m| !self.excluded.contains(m));
+        }
+
+        let mut best_move = None;
+        let mut child_pv = vec![];
+        for m in moves {
+            child_pv.clear();
+            let score = -self.negamax(
+                &play(board, m),
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                &mut child_pv,
+            );
+            if self.stopped {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(m);
+                pv.clear();
+                pv.push(m);
+                pv.extend_from_slice(&child_pv);
+                if alpha >= beta {
+                    if ordering::victim(board, &m).is_none() && m.promotion.is_none() {
+                        self.heuristics.cutoff(board.side_to_move(), m, depth, ply);
+                    }
+                    break;
+                }
+            }
+        }
+        let bound = if alpha >= beta {
+            Bound::Lower
+        } else if best_move.is_some() {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        // Without all the root moves the result isn't really the position's
+        if !excluding {
+            self.table
+                .store(key, depth, bound, score_to_table(alpha, ply), best_move);
+        }
+        alpha
+    }
+
+    fn quiescence(&mut self, board: &Board, ply: u32, mut alpha: i32, beta: i32) -> i32 {
+        match board.state {
+            BoardState::VariantLoss(_) => return -MATE + ply as i32,
+            BoardState::VariantWin(_) => return MATE - ply as i32,
+            BoardState::Draw => return 0,
+            _ => {}
+        }
+        if ply >= 2 * MAX_DEPTH {
+            return evaluate(board, self.params);
+        }
+        let in_check = matches!(board.state, BoardState::Check(_));
+        if !in_check {
+            let stand_pat = evaluate(board, self.params);
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+        }
+
+        // In check every evasion counts, otherwise only captures can change the material, and
+        // only those that don't lose it in the exchange are worth a look
+        let mut moves: Vec<Move> = board
+            .legal_moves()
+            .into_iter()
+            .filter(|m| {
+                in_check
+                    || ((ordering::victim(board, m).is_some() || m.promotion.is_some())
+                        && static_exchange(board, m) >= 0)
+            })
+            .collect();
+        Heuristics::order_captures(board, &mut moves);
+        if in_check && moves.is_empty() {
+            return -MATE + ply as i32;
+        }
+        for m in moves {
+            self.nodes += 1;
+            if self.out_of_budget() {
+                return 0;
+            }
+            let score = -self.quiescence(&play(board, m), ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        alpha
+    }
+}
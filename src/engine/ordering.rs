@@ -0,0 +1,161 @@
+use crate::{Board, Color, Move, Piece};
+
+use super::MAX_DEPTH;
+
+/// Piece values for exchanges. The king is worth more than anything it could win, so an
+/// exchange never ends with it capturing into a defended square.
+const EXCHANGE_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 20_000];
+
+const HASH_MOVE: i32 = i32::MAX;
+const GOOD_CAPTURE: i32 = 2_000_000;
+const KILLERS: [i32; 2] = [1_900_000, 1_800_000];
+const BAD_CAPTURE: i32 = -2_000_000;
+/// History scores stay below the killers, halving everything once one gets this big.
+const HISTORY_LIMIT: i32 = 1_000_000;
+
+fn value(piece: Piece) -> i32 {
+    EXCHANGE_VALUES[piece as usize]
+}
+
+/// The piece `m` takes, if any, including a pawn taken en passant.
+pub(crate) fn victim(board: &Board, m: &Move) -> Option<Piece> {
+    match &board.get_square(&m.to).troop {
+        Some(troop) => Some(troop.piece),
+        None if board.get_square(&m.from).troop.as_ref().unwrap().piece == Piece::Pawn
+            && Some(m.to) == board.en_passant =>
+        {
+            Some(Piece::Pawn)
+        }
+        None => None,
+    }
+}
+
+/// What `m` wins right away: the piece it takes plus whatever a pawn promotes to.
+fn material_gain(board: &Board, m: &Move) -> i32 {
+    victim(board, m).map_or(0, value)
+        + m.promotion
+            .map_or(0, |piece| value(piece) - value(Piece::Pawn))
+}
+
+/// Static exchange evaluation: the material `m` wins or loses once both sides have made every
+/// capture on the target square that pays off, cheapest attacker first. Pins are ignored.
+pub fn static_exchange(board: &Board, m: &Move) -> i32 {
+    let mut board = board.clone();
+    let mover = board.get_square(&m.from).troop.clone().unwrap();
+    let mut gains = vec![material_gain(&board, m)];
+    let mut on_square = match m.promotion {
+        Some(piece) => value(piece),
+        None => value(mover.piece),
+    };
+    if victim(&board, m).is_some() && board.get_square(&m.to).troop.is_none() {
+        // En passant: the captured pawn isn't on the target square
+        let captured = m.to.offset(0, -mover.color.forward()).unwrap();
+        board.get_mut_square(&captured).troop = None;
+    }
+    board.get_mut_square(&m.from).troop = None;
+
+    let mut side = mover.color.opponent();
+    loop {
+        // Removing troops as they capture lets the sliders behind them join in
+        let attacker = board
+            .attackers_of(m.to)
+            .into_iter()
+            .filter(|troop| troop.color == side)
+            .min_by_key(|troop| value(troop.piece))
+            .map(|troop| (troop.piece, troop.position));
+        let Some((piece, position)) = attacker else {
+            break;
+        };
+        gains.push(on_square - gains.last().unwrap());
+        on_square = value(piece);
+        board.get_mut_square(&position).troop = None;
+        side = side.opponent();
+    }
+    // Each side can stop capturing whenever carrying on would lose material
+    while gains.len() > 1 {
+        let last = gains.pop().unwrap();
+        let previous = gains.last_mut().unwrap();
+        *previous = -(-*previous).max(last);
+    }
+    gains[0]
+}
+
+/// What the search has learned about which moves tend to be good, used to try those first.
+pub(crate) struct Heuristics {
+    /// Up to two quiet moves per ply that caused a beta cutoff, most recent first.
+    killers: Vec<[Option<Move>; 2]>,
+    /// How much each quiet move has caused cutoffs, by side and from and to square.
+    history: Vec<i32>,
+}
+
+impl Heuristics {
+    pub(crate) fn new() -> Self {
+        Heuristics {
+            killers: vec![[None; 2]; 2 * MAX_DEPTH as usize + 1],
+            history: vec![0; 2 * 64 * 64],
+        }
+    }
+
+    fn history_index(color: Color, m: &Move) -> usize {
+        let color = match color {
+            Color::White => 0,
+            Color::Black => 1,
+        };
+        (color * 64 + m.from.index() as usize) * 64 + m.to.index() as usize
+    }
+
+    /// Records that quiet move `m` caused a beta cutoff at `ply` with `depth` plies left.
+    pub(crate) fn cutoff(&mut self, color: Color, m: Move, depth: u32, ply: u32) {
+        let killers = &mut self.killers[ply as usize];
+        if killers[0] != Some(m) {
+            killers[1] = killers[0];
+            killers[0] = Some(m);
+        }
+        let index = Self::history_index(color, &m);
+        self.history[index] += (depth * depth) as i32;
+        if self.history[index] >= HISTORY_LIMIT {
+            self.history.iter_mut().for_each(|score| *score /= 2);
+        }
+    }
+
+    /// Sorts `moves` so the likeliest best moves come first: the hash move, then captures that
+    /// don't lose material by most valuable victim and least valuable attacker, then killers,
+    /// then the other quiet moves by history, and the losing captures last.
+    pub(crate) fn order(
+        &self,
+        board: &Board,
+        moves: &mut [Move],
+        hash_move: Option<Move>,
+        ply: u32,
+    ) {
+        let color = board.side_to_move();
+        let killers = self.killers.get(ply as usize).copied().unwrap_or_default();
+        moves.sort_by_cached_key(|m| {
+            let score = if Some(*m) == hash_move {
+                HASH_MOVE
+            } else if victim(board, m).is_some() || m.promotion.is_some() {
+                let attacker = value(board.get_square(&m.from).troop.as_ref().unwrap().piece);
+                let mvv_lva = material_gain(board, m) * 16 - attacker / 100;
+                match static_exchange(board, m) >= 0 {
+                    true => GOOD_CAPTURE + mvv_lva,
+                    false => BAD_CAPTURE + mvv_lva,
+                }
+            } else if killers[0] == Some(*m) {
+                KILLERS[0]
+            } else if killers[1] == Some(*m) {
+                KILLERS[1]
+            } else {
+                self.history[Self::history_index(color, m)]
+            };
+            std::cmp::Reverse(score)
+        });
+    }
+
+    /// Sorts captures for quiescence search by most valuable victim, least valuable attacker.
+    pub(crate) fn order_captures(board: &Board, moves: &mut [Move]) {
+        moves.sort_by_cached_key(|m| {
+            let attacker = value(board.get_square(&m.from).troop.as_ref().unwrap().piece);
+            std::cmp::Reverse(material_gain(board, m) * 16 - attacker / 100)
+        });
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Move;
+
+/// How a stored score relates to the position's true score. Alpha-beta only learns the exact
+/// score when it lands inside the window; otherwise it learns a bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The true score is at least this, because a move failed high.
+    Lower,
+    /// The true score is at most this, because no move beat alpha.
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: u32,
+    pub bound: Bound,
+    pub score: i32,
+    pub best_move: Option<Move>,
+    /// Which search stored the entry, so entries left over from earlier searches get replaced
+    /// first.
+    generation: u8,
+}
+
+/// A fixed-size cache of search results keyed by [`Board::zobrist_hash`](crate::Board::zobrist_hash).
+///
+/// Each key maps to a single slot. A new entry replaces the old one if it's for the same
+/// position, if the old one is from an earlier search, or if it was searched at least as deep.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    /// A table taking up about `megabytes` of memory, with room for at least one entry.
+    pub fn new(megabytes: usize) -> Self {
+        let capacity = (megabytes * 1024 * 1024 / std::mem::size_of::<Option<Entry>>()).max(1);
+        TranspositionTable {
+            entries: vec![None; capacity],
+            generation: 0,
+        }
+    }
+
+    /// Resizes the table, which clears it.
+    pub fn resize(&mut self, megabytes: usize) {
+        *self = Self::new(megabytes);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.generation = 0;
+    }
+
+    /// How many entries fit.
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// How many slots are in use, in permille, as UCI's `hashfull` reports it.
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.entries.len().min(1000);
+        let used = self.entries[..sample]
+            .iter()
+            .filter(|entry| entry.is_some())
+            .count();
+        (used * 1000 / sample) as u32
+    }
+
+    /// Marks the start of a new search, making everything stored so far a candidate for
+    /// replacement.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        (key % self.entries.len() as u64) as usize
+    }
+
+    pub fn probe(&self, key: u64) -> Option<&Entry> {
+        self.entries[self.slot(key)]
+            .as_ref()
+            .filter(|entry| entry.key == key)
+    }
+
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u32,
+        bound: Bound,
+        score: i32,
+        best_move: Option<Move>,
+    ) {
+        let generation = self.generation;
+        let slot = self.slot(key);
+        let replace = match &self.entries[slot] {
+            None => true,
+            Some(old) => old.key == key || old.generation != generation || depth >= old.depth,
+        };
+        if replace {
+            // A shallower result for the same position still knows a move worth trying first
+            let best_move = best_move.or(match &self.entries[slot] {
+                Some(old) if old.key == key => old.best_move,
+                _ => None,
+            });
+            self.entries[slot] = Some(Entry {
+                key,
+                depth,
+                bound,
+                score,
+                best_move,
+                generation,
+            });
+        }
+    }
+}
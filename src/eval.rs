@@ -0,0 +1,307 @@
+//! Static evaluation: how good a position looks without searching any further.
+//!
+//! The score is built from separate terms so it can also explain a position to a user. Every
+//! weight lives in [`EvalParams`], which can be loaded from a JSON file with the `serde` feature.
+
+use crate::variant::Variant;
+#[cfg(feature = "serde")]
+use crate::Error;
+use crate::{Bitboard, Board, Color, File, Piece, Position, Rank, Troop};
+
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParamsError {
+    Io,
+    Parse,
+}
+
+/// The weights the evaluation uses, all in centipawns. Arrays indexed by piece go pawn, knight,
+/// bishop, rook, queen, king.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EvalParams {
+    pub material: [i32; 6],
+    /// Bonus for a piece standing on a square, from white's point of view: the first row is the
+    /// eighth rank and the first column the a-file. Black's tables are mirrored.
+    pub piece_squares: [[[i32; 8]; 8]; 6],
+    /// Bonus per square a piece attacks that isn't occupied by its own side.
+    pub mobility: [i32; 6],
+    /// For each pawn with another friendly pawn on the same file.
+    pub doubled_pawn: i32,
+    /// For each pawn without friendly pawns on the neighbouring files.
+    pub isolated_pawn: i32,
+    /// For a pawn no enemy pawn can stop, by how many ranks it has advanced from its own back
+    /// rank.
+    pub passed_pawn: [i32; 8],
+    /// For each friendly pawn directly in front of the king.
+    pub pawn_shield: i32,
+    /// For each enemy attack on a square around the king.
+    pub king_attack: i32,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        EvalParams {
+            material: [100, 320, 330, 500, 900, 0],
+            piece_squares: [
+                [
+                    [0, 0, 0, 0, 0, 0, 0, 0],
+                    [50, 50, 50, 50, 50, 50, 50, 50],
+                    [10, 10, 20, 30, 30, 20, 10, 10],
+                    [5, 5, 10, 25, 25, 10, 5, 5],
+                    [0, 0, 0, 20, 20, 0, 0, 0],
+                    [5, -5, -10, 0, 0, -10, -5, 5],
+                    [5, 10, 10, -20, -20, 10, 10, 5],
+                    [0, 0, 0, 0, 0, 0, 0, 0],
+                ],
+                [
+                    [-50, -40, -30, -30, -30, -30, -40, -50],
+                    [-40, -20, 0, 0, 0, 0, -20, -40],
+                    [-30, 0, 10, 15, 15, 10, 0, -30],
+                    [-30, 5, 15, 20, 20, 15, 5, -30],
+                    [-30, 0, 15, 20, 20, 15, 0, -30],
+                    [-30, 5, 10, 15, 15, 10, 5, -30],
+                    [-40, -20, 0, 5, 5, 0, -20, -40],
+                    [-50, -40, -30, -30, -30, -30, -40, -50],
+                ],
+                [
+                    [-20, -10, -10, -10, -10, -10, -10, -20],
+                    [-10, 0, 0, 0, 0, 0, 0, -10],
+                    [-10, 0, 5, 10, 10, 5, 0, -10],
+                    [-10, 5, 5, 10, 10, 5, 5, -10],
+                    [-10, 0, 10, 10, 10, 10, 0, -10],
+                    [-10, 10, 10, 10, 10, 10, 10, -10],
+                    [-10, 5, 0, 0, 0, 0, 5, -10],
+                    [-20, -10, -10, -10, -10, -10, -10, -20],
+                ],
+                [
+                    [0, 0, 0, 0, 0, 0, 0, 0],
+                    [5, 10, 10, 10, 10, 10, 10, 5],
+                    [-5, 0, 0, 0, 0, 0, 0, -5],
+                    [-5, 0, 0, 0, 0, 0, 0, -5],
+                    [-5, 0, 0, 0, 0, 0, 0, -5],
+                    [-5, 0, 0, 0, 0, 0, 0, -5],
+                    [-5, 0, 0, 0, 0, 0, 0, -5],
+                    [0, 0, 0, 5, 5, 0, 0, 0],
+                ],
+                [
+                    [-20, -10, -10, -5, -5, -10, -10, -20],
+                    [-10, 0, 0, 0, 0, 0, 0, -10],
+                    [-10, 0, 5, 5, 5, 5, 0, -10],
+                    [-5, 0, 5, 5, 5, 5, 0, -5],
+                    [0, 0, 5, 5, 5, 5, 0, -5],
+                    [-10, 5, 5, 5, 5, 5, 0, -10],
+                    [-10, 0, 5, 0, 0, 0, 0, -10],
+                    [-20, -10, -10, -5, -5, -10, -10, -20],
+                ],
+                [
+                    [-30, -40, -40, -50, -50, -40, -40, -30],
+                    [-30, -40, -40, -50, -50, -40, -40, -30],
+                    [-30, -40, -40, -50, -50, -40, -40, -30],
+                    [-30, -40, -40, -50, -50, -40, -40, -30],
+                    [-20, -30, -30, -40, -40, -30, -30, -20],
+                    [-10, -20, -20, -20, -20, -20, -20, -10],
+                    [20, 20, 0, 0, 0, 0, 20, 20],
+                    [20, 30, 10, 0, 0, 10, 30, 20],
+                ],
+            ],
+            mobility: [0, 4, 5, 2, 1, 0],
+            doubled_pawn: -15,
+            isolated_pawn: -15,
+            passed_pawn: [0, 5, 10, 20, 35, 60, 100, 0],
+            pawn_shield: 10,
+            king_attack: -8,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EvalParams {
+    /// Parses parameters from JSON. Fields that are left out keep their default values.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|_| Error::Params(ParamsError::Parse))
+    }
+
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let json = std::fs::read_to_string(path).map_err(|_| Error::Params(ParamsError::Io))?;
+        Self::from_json(&json)
+    }
+}
+
+/// One evaluation term, split by side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Term {
+    pub white: i32,
+    pub black: i32,
+}
+
+impl Term {
+    /// The term from white's point of view.
+    pub fn net(&self) -> i32 {
+        self.white - self.black
+    }
+
+    fn add(&mut self, color: Color, value: i32) {
+        match color {
+            Color::White => self.white += value,
+            Color::Black => self.black += value,
+        }
+    }
+}
+
+/// The evaluation of a position, term by term.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Breakdown {
+    pub material: Term,
+    pub piece_squares: Term,
+    pub mobility: Term,
+    pub pawn_structure: Term,
+    pub king_safety: Term,
+}
+
+impl Breakdown {
+    /// The whole evaluation from white's point of view.
+    pub fn total(&self) -> i32 {
+        self.material.net()
+            + self.piece_squares.net()
+            + self.mobility.net()
+            + self.pawn_structure.net()
+            + self.king_safety.net()
+    }
+}
+
+/// How many ranks `position` is from `color`'s back rank.
+fn relative_rank(position: Position, color: Color) -> usize {
+    match color {
+        Color::White => position.rank as usize,
+        Color::Black => 7 - position.rank as usize,
+    }
+}
+
+fn pawn_structure(params: &EvalParams, pawns: &[&Troop], term: &mut Term) {
+    for pawn in pawns {
+        let friendly = |file: File| {
+            pawns
+                .iter()
+                .any(|other| other.color == pawn.color && other.position.file == file)
+        };
+        if pawns.iter().any(|other| {
+            other.color == pawn.color
+                && other.position.file == pawn.position.file
+                && other.position != pawn.position
+        }) {
+            term.add(pawn.color, params.doubled_pawn);
+        }
+        let neighbours = [pawn.position.offset(-1, 0), pawn.position.offset(1, 0)];
+        if !neighbours
+            .iter()
+            .flatten()
+            .any(|neighbour| friendly(neighbour.file))
+        {
+            term.add(pawn.color, params.isolated_pawn);
+        }
+
+        // Passed if no enemy pawn stands ahead of it on its own or a neighbouring file
+        let rank = relative_rank(pawn.position, pawn.color);
+        let passed = !pawns.iter().any(|other| {
+            other.color != pawn.color
+                && (other.position.file as i8 - pawn.position.file as i8).abs() <= 1
+                && relative_rank(other.position, pawn.color) > rank
+        });
+        if passed {
+            term.add(pawn.color, params.passed_pawn[rank]);
+        }
+    }
+}
+
+fn king_safety(board: &Board, params: &EvalParams, king: &Troop, term: &mut Term) {
+    for files in -1..=1 {
+        if let Some(position) = king.position.offset(files, king.color.forward()) {
+            if let Some(troop) = &board.get_square(&position).troop {
+                if troop.piece == Piece::Pawn && troop.color == king.color {
+                    term.add(king.color, params.pawn_shield);
+                }
+            }
+        }
+    }
+    let zone = board.attacks(king);
+    let enemy_attacks = board.attack_map(king.color.opponent());
+    term.add(
+        king.color,
+        params.king_attack * (zone & enemy_attacks).count() as i32,
+    );
+}
+
+/// Evaluates `board` term by term. In Antichess material counts against its owner, and the
+/// king is just another piece with no safety to look after.
+pub fn breakdown(board: &Board, params: &EvalParams) -> Breakdown {
+    let mut breakdown = Breakdown::default();
+    let antichess = board.variant() == Variant::Antichess;
+    let troops: Vec<&Troop> = board
+        .squares
+        .values()
+        .flat_map(|rank_map| rank_map.values())
+        .filter_map(|square| square.troop.as_ref())
+        .collect();
+    let occupied_by = |color: Color| -> Bitboard {
+        troops
+            .iter()
+            .filter(|troop| troop.color == color)
+            .map(|troop| troop.position)
+            .collect()
+    };
+    let white = occupied_by(Color::White);
+    let black = occupied_by(Color::Black);
+
+    for troop in &troops {
+        let piece = troop.piece as usize;
+        let material = match antichess {
+            true => -params.material[piece],
+            false => params.material[piece],
+        };
+        breakdown.material.add(troop.color, material);
+
+        let row = match troop.color {
+            Color::White => Rank::Eight as usize - troop.position.rank as usize,
+            Color::Black => troop.position.rank as usize,
+        };
+        breakdown.piece_squares.add(
+            troop.color,
+            params.piece_squares[piece][row][troop.position.file as usize],
+        );
+
+        if params.mobility[piece] != 0 {
+            let own = match troop.color {
+                Color::White => white,
+                Color::Black => black,
+            };
+            let reach = (board.attacks(troop) & !own).count() as i32;
+            breakdown
+                .mobility
+                .add(troop.color, params.mobility[piece] * reach);
+        }
+
+        if troop.piece == Piece::King && !antichess {
+            king_safety(board, params, troop, &mut breakdown.king_safety);
+        }
+    }
+
+    let pawns: Vec<&Troop> = troops
+        .iter()
+        .copied()
+        .filter(|troop| troop.piece == Piece::Pawn)
+        .collect();
+    pawn_structure(params, &pawns, &mut breakdown.pawn_structure);
+
+    breakdown
+}
+
+/// Evaluates `board` from the side to move's point of view, as a search wants it.
+pub fn evaluate(board: &Board, params: &EvalParams) -> i32 {
+    let total = breakdown(board, params).total();
+    match board.side_to_move() {
+        Color::White => total,
+        Color::Black => -total,
+    }
+}
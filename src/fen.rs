@@ -0,0 +1,442 @@
+use std::collections::HashMap;
+
+use crate::{
+    Bitboard, Board, BoardState, CastlingRights, CastlingRooks, Color, Error, File, LastMove,
+    Piece, Pocket, Position, Rank, Square, Troop, Variant,
+};
+
+impl Piece {
+    /// The FEN letter for this piece, uppercase for white and lowercase for black.
+    pub fn to_fen_char(&self, color: Color) -> char {
+        let c = match self {
+            Piece::Pawn => 'p',
+            Piece::Knight => 'n',
+            Piece::Bishop => 'b',
+            Piece::Rook => 'r',
+            Piece::Queen => 'q',
+            Piece::King => 'k',
+        };
+        match color {
+            Color::White => c.to_ascii_uppercase(),
+            Color::Black => c,
+        }
+    }
+
+    pub fn from_fen_char(c: char) -> Option<(Piece, Color)> {
+        let piece = match c.to_ascii_lowercase() {
+            'p' => Piece::Pawn,
+            'n' => Piece::Knight,
+            'b' => Piece::Bishop,
+            'r' => Piece::Rook,
+            'q' => Piece::Queen,
+            'k' => Piece::King,
+            _ => return None,
+        };
+        let color = if c.is_ascii_uppercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        Some((piece, color))
+    }
+}
+
+impl Board {
+    /// The color whose turn it is. Once the game is over this is the side that would have moved
+    /// next.
+    pub(crate) fn side_to_move(&self) -> Color {
+        self.turn
+    }
+
+    /// Describes the position in Forsyth-Edwards Notation.
+    ///
+    /// The move counters aren't tracked by the board yet, so they're always written as `0 1`.
+    /// Chess960 boards use X-FEN, which names a castling rook's file only when `KQkq` would be
+    /// ambiguous.
+    pub fn to_fen(&self) -> String {
+        self.fen(false)
+    }
+
+    /// Like [`Board::to_fen`], but with Shredder-FEN castling rights, which always name the
+    /// rooks' files, like `HAha`.
+    pub fn to_shredder_fen(&self) -> String {
+        self.fen(true)
+    }
+
+    /// The outermost of `color`'s rooks on its back rank, on the kingside or queenside of `king`.
+    fn outer_rook(
+        squares: &HashMap<File, HashMap<Rank, Square>>,
+        color: Color,
+        king: Position,
+        kingside: bool,
+    ) -> Option<File> {
+        let files: Vec<u8> = match kingside {
+            true => (king.file as u8 + 1..8).rev().collect(),
+            false => (0..king.file as u8).collect(),
+        };
+        files
+            .into_iter()
+            .map(|file| File::try_from(file + 1).unwrap())
+            .find(|file| {
+                squares[file][&king.rank]
+                    .troop
+                    .as_ref()
+                    .is_some_and(|troop| troop.piece == Piece::Rook && troop.color == color)
+            })
+    }
+
+    fn fen(&self, shredder: bool) -> String {
+        let mut fen = String::new();
+        for rank in (1..=8).rev() {
+            let rank = Rank::try_from(rank).unwrap();
+            let mut empty = 0;
+            for file in 1..=8 {
+                let file = File::try_from(file).unwrap();
+                match &self.squares[&file][&rank].troop {
+                    Some(troop) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push(troop.piece.to_fen_char(troop.color));
+                        if self.drops() && self.promoted.contains(troop.position) {
+                            fen.push('~');
+                        }
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank != Rank::One {
+                fen.push('/');
+            }
+        }
+        if self.drops() {
+            fen.push('[');
+            for color in [Color::White, Color::Black] {
+                for piece in Pocket::PIECES {
+                    for _ in 0..self.pocket(color).count(piece) {
+                        fen.push(piece.to_fen_char(color));
+                    }
+                }
+            }
+            fen.push(']');
+        }
+        fen.push_str(match self.side_to_move() {
+            Color::White => " w",
+            Color::Black => " b",
+        });
+        fen.push(' ');
+        let rooks = self.castling_rooks;
+        let castling = [
+            (
+                self.castling.white_kingside,
+                Color::White,
+                rooks.white_kingside,
+                true,
+            ),
+            (
+                self.castling.white_queenside,
+                Color::White,
+                rooks.white_queenside,
+                false,
+            ),
+            (
+                self.castling.black_kingside,
+                Color::Black,
+                rooks.black_kingside,
+                true,
+            ),
+            (
+                self.castling.black_queenside,
+                Color::Black,
+                rooks.black_queenside,
+                false,
+            ),
+        ]
+        .into_iter()
+        .filter(|(allowed, ..)| *allowed)
+        .map(|(_, color, file, kingside)| {
+            let outer = self
+                .king(color)
+                .and_then(|king| Board::outer_rook(&self.squares, color, king, kingside))
+                == Some(file);
+            let c = match (shredder || (self.chess960 && !outer), kingside) {
+                (true, _) => (b'a' + file as u8) as char,
+                (false, true) => 'k',
+                (false, false) => 'q',
+            };
+            match color {
+                Color::White => c.to_ascii_uppercase(),
+                Color::Black => c,
+            }
+        })
+        .collect::<String>();
+        if castling.is_empty() {
+            fen.push('-');
+        } else {
+            fen.push_str(&castling);
+        }
+        fen.push(' ');
+        match self.en_passant {
+            Some(position) => fen.push_str(&position.to_string()),
+            None => fen.push('-'),
+        }
+        fen.push_str(" 0 1");
+        if self.variant == Variant::ThreeCheck {
+            fen.push_str(&format!(
+                " +{}+{}",
+                self.checks_given(Color::White),
+                self.checks_given(Color::Black)
+            ));
+        }
+        fen
+    }
+
+    /// Builds a board from a FEN string. Each side needs exactly one king.
+    ///
+    /// The move counters may be omitted, and are ignored if present. Castling rights can be given
+    /// as `KQkq` or by the rooks' files as in Shredder-FEN and X-FEN, like `HAha` or `Bb`. A
+    /// position whose castling rooks or king aren't on their usual files is set up for Chess960.
+    /// `KQkq` name the outermost rook on each side. Crazyhouse pockets can follow the placement
+    /// in brackets, like `[Qp]`, or as a ninth row, and set the board up for Crazyhouse; pieces
+    /// that got there by promotion are marked with a `~`. A seventh field with the checks each side
+    /// has given, as Three-check writes them, is kept for [`Board::set_variant`].
+    pub fn from_fen(fen: &str) -> Result<Board, Error> {
+        Board::from_variant_fen(fen, Variant::Standard)
+    }
+
+    /// Builds a board from a FEN string of a `variant` game, like [`Board::from_fen`] but by
+    /// that variant's rules for what the position may hold, so a Horde FEN can leave out
+    /// white's king and an Antichess one can have several kings a side. Pockets in a standard
+    /// FEN make it Crazyhouse.
+    pub fn from_variant_fen(fen: &str, variant: Variant) -> Result<Board, Error> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if ![4, 6, 7].contains(&fields.len()) {
+            return Err(Error::FenParse);
+        }
+
+        let mut squares: HashMap<File, HashMap<Rank, Square>> = HashMap::new();
+        let mut white_king = None;
+        let mut black_king = None;
+        let mut pockets = [Pocket::default(); 2];
+        let mut promoted = Bitboard::EMPTY;
+        // Crazyhouse pockets come after the placement, in brackets or as a ninth row
+        let (placement, pocket) = match fields[0].strip_suffix(']') {
+            Some(rest) => {
+                let (placement, pocket) = rest.split_once('[').ok_or(Error::FenParse)?;
+                (placement, Some(pocket))
+            }
+            None => (fields[0], None),
+        };
+        let mut rows: Vec<&str> = placement.split('/').collect();
+        let pocket = match (pocket, rows.len()) {
+            (None, 9) => rows.pop(),
+            (pocket, 8) => pocket,
+            _ => return Err(Error::FenParse),
+        };
+        for c in pocket.unwrap_or("").chars() {
+            match Piece::from_fen_char(c) {
+                Some((piece, color)) if piece != Piece::King => pockets[color as usize].add(piece),
+                _ => return Err(Error::FenParse),
+            }
+        }
+        for (row, rank) in rows.iter().zip((1..=8).rev()) {
+            let rank = Rank::try_from(rank).unwrap();
+            let mut file = 1;
+            for c in row.chars() {
+                // A piece that got there by promotion is followed by a tilde
+                if c == '~' {
+                    let position = File::try_from(file - 1)
+                        .ok()
+                        .map(|file| Position { file, rank })
+                        .filter(|position| squares[&position.file][&rank].troop.is_some())
+                        .ok_or(Error::FenParse)?;
+                    promoted.insert(position);
+                    continue;
+                }
+                if let Some(skip) = c.to_digit(10) {
+                    if skip == 0 {
+                        return Err(Error::FenParse);
+                    }
+                    for _ in 0..skip {
+                        let position = Position {
+                            file: File::try_from(file).map_err(|_| Error::FenParse)?,
+                            rank,
+                        };
+                        squares.entry(position.file).or_default().insert(
+                            rank,
+                            Square {
+                                troop: None,
+                                position,
+                            },
+                        );
+                        file += 1;
+                    }
+                    continue;
+                }
+                let (piece, color) = Piece::from_fen_char(c).ok_or(Error::FenParse)?;
+                let position = Position {
+                    file: File::try_from(file).map_err(|_| Error::FenParse)?,
+                    rank,
+                };
+                // Pawns never stand on the back ranks, except for Horde's on white's first rank
+                let horde_start =
+                    variant == Variant::Horde && color == Color::White && rank == Rank::One;
+                if piece == Piece::Pawn && matches!(rank, Rank::One | Rank::Eight) && !horde_start {
+                    return Err(Error::FenParse);
+                }
+                if piece == Piece::King {
+                    let king = match color {
+                        Color::White => &mut white_king,
+                        Color::Black => &mut black_king,
+                    };
+                    // A side that doesn't need a king may have several, and the first is tracked
+                    match king {
+                        Some(_) if variant.needs_king(color) => return Err(Error::FenParse),
+                        Some(_) => {}
+                        None => *king = Some(position),
+                    }
+                }
+                squares.entry(position.file).or_default().insert(
+                    rank,
+                    Square {
+                        troop: Some(Troop {
+                            piece,
+                            color,
+                            position,
+                        }),
+                        position,
+                    },
+                );
+                file += 1;
+            }
+            if file != 9 {
+                return Err(Error::FenParse);
+            }
+        }
+
+        for (color, king) in [(Color::White, white_king), (Color::Black, black_king)] {
+            if king.is_none() && variant.needs_king(color) {
+                return Err(Error::FenParse);
+            }
+        }
+
+        let color = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(Error::FenParse),
+        };
+        let mut castling = CastlingRights::default();
+        let mut castling_rooks = CastlingRooks::default();
+        let mut chess960 = false;
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                let (color, king) = match c.is_ascii_uppercase() {
+                    true => (Color::White, white_king),
+                    false => (Color::Black, black_king),
+                };
+                // Castling rights need a king to castle with
+                let king = king.ok_or(Error::FenParse)?;
+                let (kingside, file) = match c.to_ascii_lowercase() {
+                    'k' => (
+                        true,
+                        Board::outer_rook(&squares, color, king, true).unwrap_or(File::H),
+                    ),
+                    'q' => (
+                        false,
+                        Board::outer_rook(&squares, color, king, false).unwrap_or(File::A),
+                    ),
+                    letter @ 'a'..='h' => {
+                        let file = File::try_from(letter as u8 - b'a' + 1).unwrap();
+                        chess960 = true;
+                        (file as u8 > king.file as u8, file)
+                    }
+                    _ => return Err(Error::FenParse),
+                };
+                let (right, rook) = match (color, kingside) {
+                    (Color::White, true) => (
+                        &mut castling.white_kingside,
+                        &mut castling_rooks.white_kingside,
+                    ),
+                    (Color::White, false) => (
+                        &mut castling.white_queenside,
+                        &mut castling_rooks.white_queenside,
+                    ),
+                    (Color::Black, true) => (
+                        &mut castling.black_kingside,
+                        &mut castling_rooks.black_kingside,
+                    ),
+                    (Color::Black, false) => (
+                        &mut castling.black_queenside,
+                        &mut castling_rooks.black_queenside,
+                    ),
+                };
+                *right = true;
+                *rook = file;
+                let corner = match kingside {
+                    true => File::H,
+                    false => File::A,
+                };
+                if king.file != File::E || file != corner {
+                    chess960 = true;
+                }
+            }
+        }
+        let en_passant = match fields[3] {
+            "-" => None,
+            square => Some(square.parse::<Position>().map_err(|_| Error::FenParse)?),
+        };
+        if fields.len() >= 6
+            && (fields[4].parse::<u32>().is_err() || fields[5].parse::<u32>().is_err())
+        {
+            return Err(Error::FenParse);
+        }
+        // Three-check positions can end with the checks each side has given, like `+1+0`
+        let mut checks = [0; 2];
+        if let Some(field) = fields.get(6) {
+            let counts: Vec<&str> = field.split('+').collect();
+            let ["", white, black] = counts[..] else {
+                return Err(Error::FenParse);
+            };
+            checks[Color::White as usize] = white.parse().map_err(|_| Error::FenParse)?;
+            checks[Color::Black as usize] = black.parse().map_err(|_| Error::FenParse)?;
+        }
+
+        #[allow(unused_mut)]
+        let mut board = Board {
+            squares,
+            state: BoardState::ToMove(color),
+            turn: color,
+            black_king,
+            white_king,
+            last_move: LastMove {
+                from: Position {
+                    file: File::A,
+                    rank: Rank::One,
+                },
+                to: Position {
+                    file: File::A,
+                    rank: Rank::One,
+                },
+                promotion: None,
+            },
+            castling,
+            en_passant,
+            castling_rooks,
+            chess960,
+            variant: match (pocket, variant) {
+                (Some(_), Variant::Standard) => Variant::Crazyhouse,
+                _ => variant,
+            },
+            checks,
+            pockets,
+            promoted,
+        };
+        #[cfg(feature = "actions")]
+        board.update_state(color);
+        Ok(board)
+    }
+}
@@ -0,0 +1,250 @@
+use crate::attacks::{BISHOP_DIRECTIONS, ROOK_DIRECTIONS};
+use crate::{Bitboard, Board, BoardState, Color, Move, Piece, Position, Rank, Troop, Variant};
+
+/// A troop that can't leave the line between its king and an enemy slider without exposing the
+/// king to check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pin {
+    pub pinned: Position,
+    pub pinner: Position,
+}
+
+/// The squares strictly between two squares on the same rank, file or diagonal.
+fn between(from: Position, to: Position) -> Bitboard {
+    let files = to.file as i8 - from.file as i8;
+    let ranks = to.rank as i8 - from.rank as i8;
+    if files != 0 && ranks != 0 && files.abs() != ranks.abs() {
+        return Bitboard::EMPTY;
+    }
+    let direction = (files.signum(), ranks.signum());
+    let mut squares = Bitboard::EMPTY;
+    let mut current = from;
+    while let Some(next) = current.offset(direction.0, direction.1) {
+        if next == to {
+            break;
+        }
+        squares.insert(next);
+        current = next;
+    }
+    squares
+}
+
+/// Everything about the position that constrains which moves are legal, worked out once up
+/// front instead of trying each move and checking whether it left the king in check.
+struct Constraints {
+    color: Color,
+    king: Option<Position>,
+    checkers: Vec<Position>,
+    pins: Vec<Pin>,
+    /// Squares a non-king move has to land on to resolve a single check.
+    evasion: Bitboard,
+    /// The board without the king, so slider attacks along the king's line of retreat show up.
+    kingless: Board,
+    /// Whether only captures may be played, as in Antichess when there is one.
+    must_capture: bool,
+}
+
+impl Board {
+    /// The enemy troops giving check to `color`'s king.
+    pub fn checkers(&self, color: Color) -> Vec<&Troop> {
+        let Some(king) = self.king(color) else {
+            return vec![];
+        };
+        self.attackers_of(king)
+            .into_iter()
+            .filter(|troop| troop.color != color)
+            .collect()
+    }
+
+    /// `color`'s troops that are absolutely pinned to their king.
+    pub fn pins(&self, color: Color) -> Vec<Pin> {
+        let Some(king) = self.king(color) else {
+            return vec![];
+        };
+        let mut pins = vec![];
+        for direction in ROOK_DIRECTIONS.iter().chain(BISHOP_DIRECTIONS.iter()) {
+            let slider = if ROOK_DIRECTIONS.contains(direction) {
+                Piece::Rook
+            } else {
+                Piece::Bishop
+            };
+            let mut candidate = None;
+            let mut current = king;
+            while let Some(next) = current.offset(direction.0, direction.1) {
+                if let Some(troop) = &self.get_square(&next).troop {
+                    match candidate {
+                        None if troop.color == color => candidate = Some(next),
+                        None => break,
+                        Some(pinned) => {
+                            if troop.color != color
+                                && (troop.piece == slider || troop.piece == Piece::Queen)
+                            {
+                                pins.push(Pin {
+                                    pinned,
+                                    pinner: next,
+                                });
+                            }
+                            break;
+                        }
+                    }
+                }
+                current = next;
+            }
+        }
+        pins
+    }
+
+    fn constraints(&self, color: Color) -> Constraints {
+        let king = self.king(color);
+        let checkers: Vec<Position> = self
+            .checkers(color)
+            .iter()
+            .map(|troop| troop.position)
+            .collect();
+        let mut evasion = Bitboard::EMPTY;
+        if let (Some(king), &[checker]) = (king, &checkers[..]) {
+            evasion.insert(checker);
+            if matches!(
+                self.get_square(&checker).troop.as_ref().unwrap().piece,
+                Piece::Bishop | Piece::Rook | Piece::Queen
+            ) {
+                evasion |= between(king, checker);
+            }
+        }
+        let mut kingless = self.clone();
+        if let Some(king) = king {
+            kingless.get_mut_square(&king).troop = None;
+        }
+        Constraints {
+            color,
+            king,
+            checkers,
+            pins: self.pins(color),
+            evasion,
+            kingless,
+            must_capture: self.variant == Variant::Antichess && self.can_capture(color),
+        }
+    }
+
+    fn constrained_destinations(&self, troop: &Troop, constraints: &Constraints) -> Vec<Position> {
+        let mut destinations = match self.variant {
+            Variant::Atomic => self.atomic_destinations(troop),
+            Variant::Antichess => self.antichess_destinations(troop),
+            _ => self.safe_destinations(troop, constraints),
+        };
+        destinations.retain(|to| self.variant_allows(troop, *to));
+        if constraints.must_capture {
+            destinations.retain(|to| self.captures(troop, *to));
+        }
+        destinations
+    }
+
+    /// The squares `troop` can move to without leaving its king in check.
+    fn safe_destinations(&self, troop: &Troop, constraints: &Constraints) -> Vec<Position> {
+        let enemy = constraints.color.opponent();
+        let candidates = self
+            .valid_moves(troop)
+            .into_iter()
+            .map(|square| square.position);
+
+        if troop.piece == Piece::King {
+            return candidates
+                .filter(|to| match self.castle(troop.position, *to) {
+                    // Castling moves already made sure the king ends up safe
+                    Some(_) => constraints.checkers.is_empty(),
+                    None => !constraints.kingless.is_attacked(*to, enemy),
+                })
+                .collect();
+        }
+        // In double check only the king can move
+        if constraints.checkers.len() > 1 {
+            return vec![];
+        }
+
+        let pin_line = constraints
+            .pins
+            .iter()
+            .find(|pin| pin.pinned == troop.position)
+            .zip(constraints.king)
+            .map(|(pin, king)| {
+                let mut line = between(king, pin.pinner);
+                line.insert(pin.pinner);
+                line
+            });
+        candidates
+            .filter(|to| {
+                if troop.piece == Piece::Pawn && Some(*to) == self.en_passant {
+                    // Capturing en passant takes two pawns off the same rank at once, which can
+                    // expose the king in ways pins don't describe, so just try it
+                    let mut board = self.clone();
+                    board.apply_move(Move {
+                        from: troop.position,
+                        to: *to,
+                        promotion: None,
+                    });
+                    return !board.check_for_check(constraints.color);
+                }
+                if let Some(line) = pin_line {
+                    if !line.contains(*to) {
+                        return false;
+                    }
+                }
+                constraints.checkers.is_empty() || constraints.evasion.contains(*to)
+            })
+            .collect()
+    }
+
+    /// The squares `troop` can legally move to.
+    pub fn legal_destinations(&self, troop: &Troop) -> Vec<Position> {
+        self.constrained_destinations(troop, &self.constraints(troop.color))
+    }
+
+    /// Every move the side to move can legally make. When in check these are exactly the check
+    /// evasions.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let color = match self.state {
+            BoardState::ToMove(color) | BoardState::Check(color) => color,
+            _ => return vec![],
+        };
+
+        let constraints = self.constraints(color);
+        let promotions: &[Piece] = match self.variant {
+            Variant::Antichess => &[
+                Piece::Queen,
+                Piece::Rook,
+                Piece::Bishop,
+                Piece::Knight,
+                Piece::King,
+            ],
+            _ => &[Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight],
+        };
+        let mut legal_moves = vec![];
+        let troops = self
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+            .filter(|troop| troop.color == color);
+        for troop in troops {
+            for to in self.constrained_destinations(troop, &constraints) {
+                if troop.piece == Piece::Pawn && matches!(to.rank, Rank::One | Rank::Eight) {
+                    for piece in promotions {
+                        legal_moves.push(Move {
+                            from: troop.position,
+                            to,
+                            promotion: Some(*piece),
+                        });
+                    }
+                } else {
+                    legal_moves.push(Move {
+                        from: troop.position,
+                        to,
+                        promotion: None,
+                    });
+                }
+            }
+        }
+        legal_moves.sort_by_key(|m| (m.from, m.to));
+        legal_moves
+    }
+}
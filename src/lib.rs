@@ -1,5 +1,57 @@
+#[cfg(feature = "actions")]
+pub mod annotate;
+#[cfg(feature = "actions")]
+mod antichess;
+#[cfg(feature = "actions")]
+mod atomic;
+#[cfg(feature = "actions")]
+mod attacks;
+mod bitboard;
+#[cfg(feature = "actions")]
+pub mod book;
+#[cfg(feature = "actions")]
+mod builder;
+mod chess960;
+#[cfg(feature = "actions")]
+pub mod client;
+mod crazyhouse;
+#[cfg(feature = "actions")]
+pub mod engine;
+#[cfg(feature = "actions")]
+pub mod eval;
+mod fen;
+#[cfg(feature = "actions")]
+mod legal;
+#[cfg(feature = "actions")]
+pub mod pgn;
+pub mod render;
+#[cfg(feature = "actions")]
+mod san;
+#[cfg(feature = "serde")]
+pub mod store;
+pub mod svg;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "actions")]
+pub mod uci;
+mod validate;
+pub mod variant;
+#[cfg(all(feature = "serde", feature = "actions"))]
+pub mod wire;
+#[cfg(feature = "actions")]
+pub mod xboard;
+mod zobrist;
+
+pub use bitboard::Bitboard;
+#[cfg(feature = "actions")]
+pub use builder::{BoardBuilder, SetupError};
+#[cfg(feature = "actions")]
+pub use crazyhouse::Bughouse;
+pub use crazyhouse::{DropMove, Pocket};
+#[cfg(feature = "actions")]
+pub use legal::Pin;
+pub use validate::Inconsistency;
+pub use variant::Variant;
 
 use std::collections::HashMap;
 
@@ -7,7 +59,24 @@ use std::collections::HashMap;
 pub enum Error {
     RankParse,
     FileParse,
+    FenParse,
+    BoardParse,
+    #[cfg(feature = "actions")]
+    SanParse,
+    InvalidBoard(Vec<Inconsistency>),
+    #[cfg(feature = "actions")]
+    Setup(SetupError),
     Move(MoveError),
+    #[cfg(feature = "serde")]
+    Store(store::StoreError),
+    #[cfg(all(feature = "serde", feature = "actions"))]
+    Wire(wire::WireError),
+    #[cfg(all(feature = "serde", feature = "actions"))]
+    Params(eval::ParamsError),
+    #[cfg(feature = "actions")]
+    Book(book::BookError),
+    #[cfg(feature = "actions")]
+    Engine(engine::EngineError),
 }
 #[cfg(feature = "actix")]
 impl From<Error> for actix_web::Error {
@@ -15,6 +84,34 @@ impl From<Error> for actix_web::Error {
         match err {
             Error::RankParse => actix_web::error::ErrorBadRequest("Invalid rank"),
             Error::FileParse => actix_web::error::ErrorBadRequest("Invalid file"),
+            Error::FenParse => actix_web::error::ErrorBadRequest("Invalid FEN"),
+            Error::BoardParse => actix_web::error::ErrorBadRequest("Invalid board"),
+            #[cfg(feature = "actions")]
+            Error::SanParse => actix_web::error::ErrorBadRequest("Invalid SAN move"),
+            Error::InvalidBoard(inconsistencies) => actix_web::error::ErrorBadRequest(format!(
+                "Inconsistent board: {}",
+                inconsistencies
+                    .iter()
+                    .map(|inconsistency| inconsistency.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            #[cfg(feature = "actions")]
+            Error::Setup(setup_error) => match setup_error {
+                SetupError::KingCount(_) => {
+                    actix_web::error::ErrorBadRequest("Each side needs exactly one king")
+                }
+                SetupError::PawnOnBackRank(_) => {
+                    actix_web::error::ErrorBadRequest("Pawns can't stand on the back ranks")
+                }
+                SetupError::OpponentInCheck => {
+                    actix_web::error::ErrorBadRequest("The side not to move is in check")
+                }
+                SetupError::CastlingRights => {
+                    actix_web::error::ErrorBadRequest("Invalid castling rights")
+                }
+                SetupError::EnPassant => actix_web::error::ErrorBadRequest("Invalid en passant"),
+            },
             Error::Move(move_error) => match move_error {
                 MoveError::EmptyStartingSquare => {
                     actix_web::error::ErrorBadRequest("Starting square is empty")
@@ -27,6 +124,58 @@ impl From<Error> for actix_web::Error {
                 MoveError::NoMotion => actix_web::error::ErrorBadRequest("No motion"),
                 MoveError::GameOver => actix_web::error::ErrorBadRequest("Game over"),
             },
+            Error::Store(store_error) => match store_error {
+                store::StoreError::NotFound => actix_web::error::ErrorNotFound("Game not found"),
+                store::StoreError::Io => {
+                    actix_web::error::ErrorInternalServerError("Game storage failed")
+                }
+                store::StoreError::Serialization => {
+                    actix_web::error::ErrorInternalServerError("Stored game is corrupt")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Wire(wire_error) => match wire_error {
+                wire::WireError::Json => actix_web::error::ErrorBadRequest("Invalid JSON"),
+                wire::WireError::UnsupportedVersion => {
+                    actix_web::error::ErrorBadRequest("Unsupported schema version")
+                }
+                wire::WireError::StateMismatch => {
+                    actix_web::error::ErrorBadRequest("State doesn't match position")
+                }
+                wire::WireError::LegalMovesMismatch => {
+                    actix_web::error::ErrorBadRequest("Legal moves don't match position")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Params(params_error) => match params_error {
+                eval::ParamsError::Io => actix_web::error::ErrorInternalServerError(
+                    "Couldn't read evaluation parameters",
+                ),
+                eval::ParamsError::Parse => {
+                    actix_web::error::ErrorInternalServerError("Invalid evaluation parameters")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Book(book_error) => match book_error {
+                book::BookError::Io => {
+                    actix_web::error::ErrorInternalServerError("Couldn't read opening book")
+                }
+                book::BookError::Format => {
+                    actix_web::error::ErrorInternalServerError("Invalid opening book")
+                }
+                book::BookError::Keys => {
+                    actix_web::error::ErrorInternalServerError("Invalid Polyglot key table")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Engine(engine_error) => match engine_error {
+                engine::EngineError::UnsupportedVariant(variant) => {
+                    actix_web::error::ErrorBadRequest(format!(
+                        "The engine can't play {}",
+                        variant.name()
+                    ))
+                }
+            },
         }
     }
 }
@@ -37,6 +186,34 @@ impl From<Error> for lambda_runtime::Error {
         match err {
             Error::RankParse => lambda_runtime::Error::from("Invalid rank"),
             Error::FileParse => lambda_runtime::Error::from("Invalid file"),
+            Error::FenParse => lambda_runtime::Error::from("Invalid FEN"),
+            Error::BoardParse => lambda_runtime::Error::from("Invalid board"),
+            #[cfg(feature = "actions")]
+            Error::SanParse => lambda_runtime::Error::from("Invalid SAN move"),
+            Error::InvalidBoard(inconsistencies) => lambda_runtime::Error::from(format!(
+                "Inconsistent board: {}",
+                inconsistencies
+                    .iter()
+                    .map(|inconsistency| inconsistency.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            #[cfg(feature = "actions")]
+            Error::Setup(setup_error) => match setup_error {
+                SetupError::KingCount(_) => {
+                    lambda_runtime::Error::from("Each side needs exactly one king")
+                }
+                SetupError::PawnOnBackRank(_) => {
+                    lambda_runtime::Error::from("Pawns can't stand on the back ranks")
+                }
+                SetupError::OpponentInCheck => {
+                    lambda_runtime::Error::from("The side not to move is in check")
+                }
+                SetupError::CastlingRights => {
+                    lambda_runtime::Error::from("Invalid castling rights")
+                }
+                SetupError::EnPassant => lambda_runtime::Error::from("Invalid en passant"),
+            },
             Error::Move(move_error) => match move_error {
                 MoveError::EmptyStartingSquare => {
                     lambda_runtime::Error::from("Starting square is empty")
@@ -49,6 +226,47 @@ impl From<Error> for lambda_runtime::Error {
                 MoveError::NoMotion => lambda_runtime::Error::from("No motion"),
                 MoveError::GameOver => lambda_runtime::Error::from("Game over"),
             },
+            Error::Store(store_error) => match store_error {
+                store::StoreError::NotFound => lambda_runtime::Error::from("Game not found"),
+                store::StoreError::Io => lambda_runtime::Error::from("Game storage failed"),
+                store::StoreError::Serialization => {
+                    lambda_runtime::Error::from("Stored game is corrupt")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Wire(wire_error) => match wire_error {
+                wire::WireError::Json => lambda_runtime::Error::from("Invalid JSON"),
+                wire::WireError::UnsupportedVersion => {
+                    lambda_runtime::Error::from("Unsupported schema version")
+                }
+                wire::WireError::StateMismatch => {
+                    lambda_runtime::Error::from("State doesn't match position")
+                }
+                wire::WireError::LegalMovesMismatch => {
+                    lambda_runtime::Error::from("Legal moves don't match position")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Params(params_error) => match params_error {
+                eval::ParamsError::Io => {
+                    lambda_runtime::Error::from("Couldn't read evaluation parameters")
+                }
+                eval::ParamsError::Parse => {
+                    lambda_runtime::Error::from("Invalid evaluation parameters")
+                }
+            },
+            #[cfg(feature = "actions")]
+            Error::Book(book_error) => match book_error {
+                book::BookError::Io => lambda_runtime::Error::from("Couldn't read opening book"),
+                book::BookError::Format => lambda_runtime::Error::from("Invalid opening book"),
+                book::BookError::Keys => lambda_runtime::Error::from("Invalid Polyglot key table"),
+            },
+            #[cfg(feature = "actions")]
+            Error::Engine(engine_error) => match engine_error {
+                engine::EngineError::UnsupportedVariant(variant) => {
+                    lambda_runtime::Error::from(format!("The engine can't play {}", variant.name()))
+                }
+            },
         }
     }
 }
@@ -72,9 +290,34 @@ pub enum MoveError {
 pub struct Board {
     pub squares: HashMap<File, HashMap<Rank, Square>>,
     pub state: BoardState,
-    black_king: Position,
-    white_king: Position,
+    /// The side to move, or that would have moved next once the game is over.
+    #[cfg_attr(feature = "serde", serde(default))]
+    turn: Color,
+    /// Where each side's king stands, or `None` for a side playing without one, like white in
+    /// Horde, or whose king has been captured.
+    black_king: Option<Position>,
+    white_king: Option<Position>,
     last_move: LastMove,
+    #[cfg_attr(feature = "serde", serde(default))]
+    castling: CastlingRights,
+    #[cfg_attr(feature = "serde", serde(default))]
+    en_passant: Option<Position>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    castling_rooks: CastlingRooks,
+    /// Whether castling is written as the king taking its own rook, as in Chess960.
+    #[cfg_attr(feature = "serde", serde(default))]
+    chess960: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    variant: Variant,
+    /// The checks each side has given, indexed by color, for Three-check.
+    #[cfg_attr(feature = "serde", serde(default))]
+    checks: [u8; 2],
+    /// The pieces each side has in hand, indexed by color, for Crazyhouse and Bughouse.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pockets: [Pocket; 2],
+    /// Pieces that got there by promotion, which go back in a pocket as pawns.
+    #[cfg_attr(feature = "serde", serde(default))]
+    promoted: Bitboard,
 }
 
 #[derive(Debug, Clone)]
@@ -82,7 +325,48 @@ pub struct Board {
 struct LastMove {
     from: Position,
     to: Position,
-    captured_troop: Option<Troop>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    promotion: Option<Piece>,
+}
+
+/// Which castling moves each side may still make, as far as king and rook moves are concerned.
+/// Whether a castling move is currently playable also depends on the squares in between.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+impl CastlingRights {
+    pub const ALL: CastlingRights = CastlingRights {
+        white_kingside: true,
+        white_queenside: true,
+        black_kingside: true,
+        black_queenside: true,
+    };
+}
+
+/// The files the castling rooks start on, which are the corners except in Chess960.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CastlingRooks {
+    pub white_kingside: File,
+    pub white_queenside: File,
+    pub black_kingside: File,
+    pub black_queenside: File,
+}
+
+impl Default for CastlingRooks {
+    fn default() -> Self {
+        CastlingRooks {
+            white_kingside: File::H,
+            white_queenside: File::A,
+            black_kingside: File::H,
+            black_queenside: File::A,
+        }
+    }
 }
 
 impl Default for Board {
@@ -164,14 +448,15 @@ impl Default for Board {
         Board {
             squares,
             state: BoardState::ToMove(Color::White),
-            black_king: Position {
+            turn: Color::White,
+            black_king: Some(Position {
                 file: File::E,
                 rank: Rank::Eight,
-            },
-            white_king: Position {
+            }),
+            white_king: Some(Position {
                 file: File::E,
                 rank: Rank::One,
-            },
+            }),
             last_move: LastMove {
                 from: Position {
                     file: File::A,
@@ -181,92 +466,113 @@ impl Default for Board {
                     file: File::A,
                     rank: Rank::One,
                 },
-                captured_troop: None,
+                promotion: None,
             },
+            castling: CastlingRights::ALL,
+            en_passant: None,
+            castling_rooks: CastlingRooks::default(),
+            chess960: false,
+            variant: Variant::Standard,
+            checks: [0; 2],
+            pockets: [Pocket::default(); 2],
+            promoted: Bitboard::EMPTY,
         }
     }
 }
 
+/// Draws the board from white's side with coordinates, using [`render::Renderer`]'s defaults.
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut board = String::new();
-        for rank in 1..=8 {
-            for file in 1..=8 {
-                let file = File::try_from(file).unwrap();
-                let rank = Rank::try_from(rank).unwrap();
-                let square = self.squares.get(&file).unwrap().get(&rank).unwrap();
-                let troop = match square.troop {
-                    Some(ref troop) => match troop.color {
-                        Color::White => match troop.piece {
-                            Piece::Pawn => '♙',
-                            Piece::Knight => '♘',
-                            Piece::Bishop => '♗',
-                            Piece::Rook => '♖',
-                            Piece::Queen => '♕',
-                            Piece::King => '♔',
-                        },
-                        Color::Black => match troop.piece {
-                            Piece::Pawn => '♟',
-                            Piece::Knight => '♞',
-                            Piece::Bishop => '♝',
-                            Piece::Rook => '♜',
-                            Piece::Queen => '♛',
-                            Piece::King => '♚',
-                        },
-                    },
-                    None => '.',
-                };
-                board.push(troop);
-            }
-            board.push('\n');
+        write!(f, "{}", render::Renderer::default().render(self))
+    }
+}
+
+impl Board {
+    /// Where `color`'s king stands, if it has one.
+    pub fn king(&self, color: Color) -> Option<Position> {
+        match color {
+            Color::White => self.white_king,
+            Color::Black => self.black_king,
+        }
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling
+    }
+
+    /// The files the rooks each castling right belongs to started on.
+    pub fn castling_rooks(&self) -> CastlingRooks {
+        self.castling_rooks
+    }
+
+    /// Whether castling moves are written as the king taking its own rook, like `e1h1`, as
+    /// UCI_Chess960 does, instead of by the king's destination.
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    /// Switches how castling moves are written. Positions where castling leaves the king where it
+    /// is can only castle in Chess960 mode.
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+    }
+
+    /// The square a pawn may capture en passant onto this turn, if any.
+    pub fn en_passant(&self) -> Option<Position> {
+        self.en_passant
+    }
+
+    /// The most recent move, if any moves have been made since the board was set up.
+    pub fn last_move(&self) -> Option<Move> {
+        if self.last_move.from == self.last_move.to {
+            return None;
         }
-        write!(f, "{}", board)
+        Some(Move {
+            from: self.last_move.from,
+            to: self.last_move.to,
+            promotion: self.last_move.promotion,
+        })
     }
 }
 
+/// Where the king and rook go in one of a side's castling moves.
 #[cfg(feature = "actions")]
-impl Board {
-    fn rollback(&mut self, last_move: LastMove) {
-        self.get_mut_square(&last_move.from).troop =
-            self.get_square(&last_move.to).troop.as_ref().cloned();
-        self.get_mut_square(&last_move.to).troop = last_move.captured_troop;
-        self.get_mut_square(&last_move.from)
-            .troop
-            .as_mut()
-            .unwrap()
-            .position = last_move.from;
-        if let Some(troop) = &self.get_square(&last_move.from).troop {
-            if troop.piece == Piece::King {
-                match troop.color {
-                    Color::White => self.white_king = last_move.from,
-                    Color::Black => self.black_king = last_move.from,
-                }
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Castle {
+    pub(crate) king_from: Position,
+    pub(crate) king_to: Position,
+    pub(crate) rook_from: Position,
+    pub(crate) rook_to: Position,
+}
+
+#[cfg(feature = "actions")]
+impl Castle {
+    /// The square the move is written as going to.
+    pub(crate) fn target(&self, chess960: bool) -> Position {
+        match chess960 {
+            true => self.rook_from,
+            false => self.king_to,
         }
     }
 
+    pub(crate) fn kingside(&self) -> bool {
+        self.king_to.file == File::G
+    }
+}
+
+#[cfg(feature = "actions")]
+impl Board {
     pub fn check_for_check(&self, color: Color) -> bool {
-        let king_position = match color {
-            Color::White => self.white_king,
-            Color::Black => self.black_king,
+        if self.variant == Variant::Antichess {
+            return false;
+        }
+        let Some(king) = self.king(color) else {
+            return false;
         };
-
-        let enemy_troops = self
-            .squares
-            .iter()
-            .flat_map(|(_, rank_map)| rank_map.iter())
-            .filter_map(|(_, square)| square.troop.as_ref())
-            .filter(|troop| troop.color != color);
-        for enemy_troop in enemy_troops {
-            if self
-                .valid_moves(enemy_troop)
-                .contains(&self.get_square(&king_position))
-            {
-                return true;
-            }
+        if self.variant == Variant::Atomic {
+            return self.atomic_check(color);
         }
-
-        false
+        self.is_attacked(king, color.opponent())
     }
 
     pub fn get_square(&self, position: &Position) -> &Square {
@@ -285,8 +591,29 @@ impl Board {
             .unwrap()
     }
 
+    /// Moves the troop on `from` to `to`. Pawns reaching the last rank become queens.
     pub fn move_troop(&mut self, from: Position, to: Position) -> Result<(), Error> {
-        if let BoardState::Checkmate(_) = self.state {
+        self.make_move(Move {
+            from,
+            to,
+            promotion: None,
+        })
+    }
+
+    /// Plays `m`. A pawn reaching the last rank becomes `m.promotion`, or a queen if that's
+    /// `None`.
+    pub fn make_move(&mut self, m: Move) -> Result<(), Error> {
+        let Move {
+            from,
+            to,
+            promotion,
+        } = m;
+        if let BoardState::Checkmate(_)
+        | BoardState::Stalemate
+        | BoardState::Draw
+        | BoardState::VariantLoss(_)
+        | BoardState::VariantWin(_) = self.state
+        {
             return Err(Error::Move(MoveError::GameOver));
         }
 
@@ -300,60 +627,349 @@ impl Board {
             return Err(Error::Move(MoveError::NotYourTurn));
         }
         if let Some(troop) = &to_square.troop {
-            if troop.color == from_troop.color {
+            // Chess960 castling is written as the king taking its own rook
+            if troop.color == from_troop.color && self.castle(from, to).is_none() {
                 return Err(Error::Move(MoveError::FriendlyFire));
             }
         }
 
-        if !self.valid_moves(&from_troop).contains(&to_square) {
+        if !self.legal_destinations(&from_troop).contains(&to) {
             return Err(Error::Move(MoveError::Other));
         }
+        let promotes =
+            from_troop.piece == Piece::Pawn && matches!(to.rank, Rank::One | Rank::Eight);
+        let promotion = match (promotes, promotion) {
+            (false, None) => None,
+            (true, None) => Some(Piece::Queen),
+            (true, Some(Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen)) => promotion,
+            (true, Some(Piece::King)) if self.variant == Variant::Antichess => promotion,
+            _ => return Err(Error::Move(MoveError::Other)),
+        };
+
+        self.apply_move(Move {
+            from,
+            to,
+            promotion,
+        });
+        self.update_state(from_troop.color.opponent());
+
+        Ok(())
+    }
 
+    /// Moves the troop on `from` to `to`, along with everything that comes with it: the rook
+    /// when castling, the captured pawn when capturing en passant, and the castling and en
+    /// passant bookkeeping. Doesn't check that the move is legal or update the state.
+    pub(crate) fn apply_move(&mut self, m: Move) {
+        let color = self.get_square(&m.from).troop.as_ref().unwrap().color;
+        self.move_pieces(m);
+        self.count_check(color);
+    }
+
+    fn move_pieces(&mut self, m: Move) {
+        let Move {
+            from,
+            to,
+            promotion,
+        } = m;
+        let from_troop = self.get_square(&from).troop.clone().unwrap();
+        let castle = self.castle(from, to);
         self.last_move = LastMove {
             from,
             to,
-            captured_troop: to_square.troop.clone(),
+            promotion,
+        };
+
+        if let Some(castle) = castle {
+            // Take both off first, since in Chess960 either may land where the other stood
+            let mut king = self.get_mut_square(&castle.king_from).troop.take().unwrap();
+            let mut rook = self.get_mut_square(&castle.rook_from).troop.take().unwrap();
+            king.position = castle.king_to;
+            rook.position = castle.rook_to;
+            self.get_mut_square(&castle.king_to).troop = Some(king);
+            self.get_mut_square(&castle.rook_to).troop = Some(rook);
+            *self.king_mut(from_troop.color) = Some(castle.king_to);
+            self.en_passant = None;
+            self.clear_castling_rights(from_troop.color);
+            return;
+        }
+
+        let captured = match (&self.get_square(&to).troop, from_troop.piece) {
+            (Some(_), _) => Some(to),
+            (None, Piece::Pawn) if Some(to) == self.en_passant => Some(Position {
+                file: to.file,
+                rank: from.rank,
+            }),
+            _ => None,
         };
+        self.pocket_capture(m, captured);
 
         self.get_mut_square(&to).troop = Some(from_troop.clone());
         self.get_mut_square(&from).troop = None;
 
         self.get_mut_square(&to).troop.as_mut().unwrap().position = to;
+        if let Some(piece) = promotion {
+            self.get_mut_square(&to).troop.as_mut().unwrap().piece = piece;
+        }
 
-        if self.get_square(&to).troop.as_ref().unwrap().piece == Piece::King {
-            match self.get_square(&to).troop.as_ref().unwrap().color {
-                Color::White => self.white_king = to,
-                Color::Black => self.black_king = to,
-            }
+        if from_troop.piece == Piece::King {
+            *self.king_mut(from_troop.color) = Some(to);
+            self.clear_castling_rights(from_troop.color);
         }
 
-        if self.check_for_check(from_troop.color) {
-            self.rollback(self.last_move.clone());
-            return Err(Error::Move(MoveError::Other));
+        if from_troop.piece == Piece::Pawn && Some(to) == self.en_passant {
+            self.get_mut_square(&Position {
+                file: to.file,
+                rank: from.rank,
+            })
+            .troop = None;
         }
+        self.en_passant = match (from_troop.piece, from.rank, to.rank) {
+            (Piece::Pawn, Rank::Two, Rank::Four) | (Piece::Pawn, Rank::Seven, Rank::Five) => {
+                Some(Position {
+                    file: from.file,
+                    rank: Rank::try_from((from.rank as u8 + to.rank as u8) / 2 + 1).unwrap(),
+                })
+            }
+            _ => None,
+        };
 
-        match self.state {
-            BoardState::ToMove(Color::White) | BoardState::Check(Color::White) => {
-                if self.check_for_check(Color::Black) {
-                    self.state = BoardState::Check(Color::Black);
-                } else {
-                    self.state = BoardState::ToMove(Color::Black);
+        let mut vacated = vec![from, to];
+        if captured.is_some() {
+            vacated.extend(self.explode(to));
+        }
+
+        // Moving a castling rook or capturing it loses the right that goes with it
+        let rooks = self.castling_rooks;
+        for position in vacated {
+            for (rank, file, right) in [
+                (
+                    Rank::One,
+                    rooks.white_kingside,
+                    &mut self.castling.white_kingside,
+                ),
+                (
+                    Rank::One,
+                    rooks.white_queenside,
+                    &mut self.castling.white_queenside,
+                ),
+                (
+                    Rank::Eight,
+                    rooks.black_kingside,
+                    &mut self.castling.black_kingside,
+                ),
+                (
+                    Rank::Eight,
+                    rooks.black_queenside,
+                    &mut self.castling.black_queenside,
+                ),
+            ] {
+                if position == (Position { file, rank }) {
+                    *right = false;
                 }
             }
-            BoardState::ToMove(Color::Black) | BoardState::Check(Color::Black) => {
-                if self.check_for_check(Color::White) {
-                    self.state = BoardState::Check(Color::White);
-                } else {
-                    self.state = BoardState::ToMove(Color::White);
-                }
+        }
+        for color in [Color::White, Color::Black] {
+            self.track_king(color);
+        }
+    }
+
+    fn king_mut(&mut self, color: Color) -> &mut Option<Position> {
+        match color {
+            Color::White => &mut self.white_king,
+            Color::Black => &mut self.black_king,
+        }
+    }
+
+    /// Finds `color`'s king again if the move lost track of it, since in Atomic and Antichess
+    /// kings can be captured or exploded, and in Antichess pawns can promote to one.
+    fn track_king(&mut self, color: Color) {
+        let is_king = |square: &Square| {
+            square
+                .troop
+                .as_ref()
+                .is_some_and(|troop| troop.piece == Piece::King && troop.color == color)
+        };
+        if self
+            .king(color)
+            .is_some_and(|king| is_king(self.get_square(&king)))
+        {
+            return;
+        }
+        let king = self
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .find(|square| is_king(square))
+            .map(|square| square.position);
+        *self.king_mut(color) = king;
+        if king.is_none() {
+            self.clear_castling_rights(color);
+        }
+    }
+
+    fn clear_castling_rights(&mut self, color: Color) {
+        match color {
+            Color::White => {
+                self.castling.white_kingside = false;
+                self.castling.white_queenside = false;
+            }
+            Color::Black => {
+                self.castling.black_kingside = false;
+                self.castling.black_queenside = false;
             }
-            _ => unreachable!(),
         }
+    }
 
-        Ok(())
+    /// Works out the state for when it's `color`'s turn.
+    pub(crate) fn update_state(&mut self, color: Color) {
+        self.turn = color;
+        self.state = BoardState::ToMove(color);
+        if let Some(state) = self.variant_outcome(color) {
+            self.state = state;
+            return;
+        }
+        let in_check = self.check_for_check(color);
+        if self.legal_moves().is_empty() && self.legal_drops().is_empty() {
+            self.state = match in_check {
+                true => BoardState::Checkmate(color),
+                false => BoardState::Stalemate,
+            };
+        } else if in_check {
+            self.state = BoardState::Check(color);
+        }
     }
 
+    /// The squares `troop` could move to, ignoring whether the move would leave its own king in
+    /// check.
     pub fn valid_moves(&self, troop: &Troop) -> Vec<&Square> {
+        let mut valid_moves = self.reachable_squares(troop);
+        if troop.piece == Piece::King {
+            valid_moves.extend(self.castling_moves(troop));
+        }
+        valid_moves
+    }
+
+    /// The castling moves `color` still has the rights for, wherever its king stands now.
+    pub(crate) fn castles(&self, color: Color) -> Vec<Castle> {
+        let Some(king) = self.king(color) else {
+            return vec![];
+        };
+        let (rank, rights) = match color {
+            Color::White => (
+                Rank::One,
+                [
+                    (
+                        self.castling.white_kingside,
+                        self.castling_rooks.white_kingside,
+                        File::G,
+                        File::F,
+                    ),
+                    (
+                        self.castling.white_queenside,
+                        self.castling_rooks.white_queenside,
+                        File::C,
+                        File::D,
+                    ),
+                ],
+            ),
+            Color::Black => (
+                Rank::Eight,
+                [
+                    (
+                        self.castling.black_kingside,
+                        self.castling_rooks.black_kingside,
+                        File::G,
+                        File::F,
+                    ),
+                    (
+                        self.castling.black_queenside,
+                        self.castling_rooks.black_queenside,
+                        File::C,
+                        File::D,
+                    ),
+                ],
+            ),
+        };
+        if king.rank != rank {
+            return vec![];
+        }
+        rights
+            .into_iter()
+            .filter(|(allowed, ..)| *allowed)
+            .map(|(_, rook, king_to, rook_to)| Castle {
+                king_from: king,
+                king_to: Position {
+                    file: king_to,
+                    rank,
+                },
+                rook_from: Position { file: rook, rank },
+                rook_to: Position {
+                    file: rook_to,
+                    rank,
+                },
+            })
+            .collect()
+    }
+
+    /// The castling move `from`-`to` is written for, if it is one: the king moving to its
+    /// destination, or in Chess960 mode taking its own rook.
+    pub(crate) fn castle(&self, from: Position, to: Position) -> Option<Castle> {
+        let color = self.get_square(&from).troop.as_ref()?.color;
+        self.castles(color)
+            .into_iter()
+            .find(|castle| castle.king_from == from && castle.target(self.chess960) == to)
+    }
+
+    fn castling_moves(&self, king: &Troop) -> Vec<&Square> {
+        if self.check_for_check(king.color) {
+            return vec![];
+        }
+        let files = |a: File, b: File| {
+            let (a, b) = (a as u8, b as u8);
+            (a.min(b)..=a.max(b)).map(|file| File::try_from(file + 1).unwrap())
+        };
+
+        let mut castling_moves = vec![];
+        for castle in self.castles(king.color) {
+            if castle.king_from != king.position {
+                continue;
+            }
+            match &self.get_square(&castle.rook_from).troop {
+                Some(rook) if rook.piece == Piece::Rook && rook.color == king.color => {}
+                _ => continue,
+            }
+            let rank = castle.king_from.rank;
+            // Everything the king and rook cross has to be empty, apart from the two of them
+            let blocked = files(castle.king_from.file, castle.king_to.file)
+                .chain(files(castle.rook_from.file, castle.rook_to.file))
+                .map(|file| Position { file, rank })
+                .filter(|position| *position != castle.king_from && *position != castle.rook_from)
+                .any(|position| self.get_square(&position).troop.is_some());
+            if blocked {
+                continue;
+            }
+            // The king may not pass through an attacked square
+            let attacked = files(castle.king_from.file, castle.king_to.file)
+                .map(|file| Position { file, rank })
+                .any(|position| self.is_attacked(position, king.color.opponent()));
+            if attacked {
+                continue;
+            }
+            // Nor land in check, which in Chess960 can come from behind the rook it moved
+            let mut after = self.clone();
+            after.apply_move(Move {
+                from: castle.king_from,
+                to: castle.target(self.chess960),
+                promotion: None,
+            });
+            if !after.check_for_check(king.color) {
+                castling_moves.push(self.get_square(&castle.target(self.chess960)));
+            }
+        }
+        castling_moves
+    }
+
+    fn reachable_squares(&self, troop: &Troop) -> Vec<&Square> {
         let mut valid_moves = vec![];
 
         match troop.piece {
@@ -367,17 +983,14 @@ impl Board {
                         }),
                     };
                     if let Some(position_in_front) = position_in_front {
-                        let troop_in_front = &self.get_square(&position_in_front).troop;
-                        match troop_in_front {
-                            Some(_) => {
-                                valid_moves.push(self.get_square(&position_in_front));
-                            }
-                            None => {
-                                valid_moves.push(self.get_square(&position_in_front));
-                            }
+                        if self.get_square(&position_in_front).troop.is_none() {
+                            valid_moves.push(self.get_square(&position_in_front));
                         }
                     }
-                    if troop.position.rank == Rank::Two {
+                    // Horde's pawns start on the first rank too, and can step two from there
+                    if troop.position.rank == Rank::Two
+                        || (troop.position.rank == Rank::One && self.variant == Variant::Horde)
+                    {
                         let position_two_in_front = Position {
                             file: troop.position.file,
                             rank: Rank::try_from(troop.position.rank as u8 + 3).unwrap(),
@@ -404,6 +1017,8 @@ impl Board {
                             if troop_diagonal_left.color != troop.color {
                                 valid_moves.push(self.get_square(&position_diagonal_left));
                             }
+                        } else if self.en_passant == Some(position_diagonal_left) {
+                            valid_moves.push(self.get_square(&position_diagonal_left));
                         }
                     }
                     let position_diagonal_right = match troop.position.file {
@@ -420,6 +1035,8 @@ impl Board {
                             if troop_diagonal_right.color != troop.color {
                                 valid_moves.push(self.get_square(&position_diagonal_right));
                             }
+                        } else if self.en_passant == Some(position_diagonal_right) {
+                            valid_moves.push(self.get_square(&position_diagonal_right));
                         }
                     }
                 }
@@ -432,14 +1049,8 @@ impl Board {
                         }),
                     };
                     if let Some(position_in_front) = position_in_front {
-                        let troop_in_front = &self.get_square(&position_in_front).troop;
-                        match troop_in_front {
-                            Some(_) => {
-                                valid_moves.push(self.get_square(&position_in_front));
-                            }
-                            None => {
-                                valid_moves.push(self.get_square(&position_in_front));
-                            }
+                        if self.get_square(&position_in_front).troop.is_none() {
+                            valid_moves.push(self.get_square(&position_in_front));
                         }
                     }
                     if troop.position.rank == Rank::Seven {
@@ -469,6 +1080,8 @@ impl Board {
                             if troop_diagonal_left.color != troop.color {
                                 valid_moves.push(self.get_square(&position_diagonal_left));
                             }
+                        } else if self.en_passant == Some(position_diagonal_left) {
+                            valid_moves.push(self.get_square(&position_diagonal_left));
                         }
                     }
                     let position_diagonal_right = match troop.position.file {
@@ -485,6 +1098,8 @@ impl Board {
                             if troop_diagonal_right.color != troop.color {
                                 valid_moves.push(self.get_square(&position_diagonal_right));
                             }
+                        } else if self.en_passant == Some(position_diagonal_right) {
+                            valid_moves.push(self.get_square(&position_diagonal_right));
                         }
                     }
                 }
@@ -782,12 +1397,12 @@ impl Board {
                 }
             }
             Piece::Queen => {
-                let bishop_moves = self.valid_moves(&Troop {
+                let bishop_moves = self.reachable_squares(&Troop {
                     piece: Piece::Bishop,
                     color: troop.color,
                     position: troop.position,
                 });
-                let rook_moves = self.valid_moves(&Troop {
+                let rook_moves = self.reachable_squares(&Troop {
                     piece: Piece::Rook,
                     color: troop.color,
                     position: troop.position,
@@ -839,6 +1454,9 @@ impl Board {
 
     #[cfg(any(test, debug_assertions))]
     pub fn set_state(&mut self, state: BoardState) {
+        if let BoardState::ToMove(color) | BoardState::Check(color) = state {
+            self.turn = color;
+        }
         self.state = state;
     }
 }
@@ -857,7 +1475,7 @@ pub enum Rank {
 }
 impl PartialOrd for Rank {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some((*self as u8).cmp(&(*other as u8)))
+        Some(self.cmp(other))
     }
 }
 impl Ord for Rank {
@@ -896,7 +1514,7 @@ pub enum File {
 }
 impl PartialOrd for File {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some((*self as u8).cmp(&(*other as u8)))
+        Some(self.cmp(other))
     }
 }
 impl Ord for File {
@@ -921,12 +1539,80 @@ impl TryFrom<u8> for File {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub file: File,
     pub rank: Rank,
 }
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            (b'a' + self.file as u8) as char,
+            self.rank as u8 + 1
+        )
+    }
+}
+impl std::str::FromStr for Position {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(file), Some(rank), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(Error::FileParse);
+        };
+        let file = match file {
+            'a'..='h' => File::try_from(file as u8 - b'a' + 1)?,
+            _ => return Err(Error::FileParse),
+        };
+        let rank = match rank {
+            '1'..='8' => Rank::try_from(rank as u8 - b'0')?,
+            _ => return Err(Error::RankParse),
+        };
+        Ok(Position { file, rank })
+    }
+}
+
+/// A move in coordinate notation, e.g. `e2e4` or `e7e8q`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move {
+    pub from: Position,
+    pub to: Position,
+    /// What a pawn reaching the last rank becomes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub promotion: Option<Piece>,
+}
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+        if let Some(piece) = self.promotion {
+            write!(f, "{}", piece.to_fen_char(Color::Black))?;
+        }
+        Ok(())
+    }
+}
+impl std::str::FromStr for Move {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() || !(4..=5).contains(&s.len()) {
+            return Err(Error::FileParse);
+        }
+        let promotion = match s[4..].chars().next() {
+            None => None,
+            Some(c) => match Piece::from_fen_char(c) {
+                Some((piece, Color::Black)) if piece != Piece::Pawn => Some(piece),
+                _ => return Err(Error::FileParse),
+            },
+        };
+        Ok(Move {
+            from: s[..2].parse()?,
+            to: s[2..4].parse()?,
+            promotion,
+        })
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -935,7 +1621,7 @@ pub struct Square {
     pub position: Position,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Piece {
     Pawn,
@@ -954,12 +1640,21 @@ pub struct Troop {
     pub position: Position,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Black,
+    #[default]
     White,
 }
+impl Color {
+    pub fn opponent(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize,))]
@@ -969,6 +1664,12 @@ pub enum BoardState {
     Checkmate(Color),
     Stalemate,
     Draw,
+    /// The color lost by a rule of the board's [`Variant`], like the other king reaching the
+    /// center in King of the Hill.
+    VariantLoss(Color),
+    /// The color, whose turn it would be, won by a rule of the board's [`Variant`], like having
+    /// no pieces left in Antichess.
+    VariantWin(Color),
 }
 #[cfg(feature = "actions")]
 impl BoardState {
@@ -0,0 +1,114 @@
+//! Writing games in Portable Game Notation.
+
+use crate::{Board, Color, Error, Move};
+
+/// PGN lines shouldn't run past this.
+const LINE_WIDTH: usize = 80;
+
+/// A move as PGN writes it, with an optional glyph and comment after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    pub color: Color,
+    pub san: String,
+    /// A Numeric Annotation Glyph, like 4 for `??`.
+    pub nag: Option<u8>,
+    pub comment: Option<String>,
+}
+
+fn header(name: &str, value: &str) -> String {
+    format!(
+        "[{} \"{}\"]\n",
+        name,
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Writes a game that started from `start`, given in FEN. `tags` fill in the header: the
+/// Seven Tag Roster is completed with `?` for anything missing, the result is always
+/// `result`, and a start other than the usual one gets `SetUp` and `FEN` tags.
+pub fn write(start: &str, moves: &[PgnMove], result: &str, tags: &[(&str, &str)]) -> String {
+    let mut pgn = String::new();
+    let tag = |name: &str| tags.iter().find(|(key, _)| *key == name).map(|(_, v)| *v);
+    for name in ["Event", "Site", "Date", "Round", "White", "Black"] {
+        let default = match name {
+            "Date" => "????.??.??",
+            _ => "?",
+        };
+        pgn.push_str(&header(name, tag(name).unwrap_or(default)));
+    }
+    pgn.push_str(&header("Result", result));
+    let roster = [
+        "Event", "Site", "Date", "Round", "White", "Black", "Result", "SetUp", "FEN",
+    ];
+    for (name, value) in tags.iter().filter(|(name, _)| !roster.contains(name)) {
+        pgn.push_str(&header(name, value));
+    }
+    if start != Board::default().to_fen() {
+        pgn.push_str(&header("SetUp", "1"));
+        pgn.push_str(&header("FEN", start));
+    }
+    pgn.push('\n');
+
+    let mut tokens = vec![];
+    let mut number = 1;
+    // A move number is due before white's moves, and before black's at the start and after a
+    // comment
+    let mut after_comment = true;
+    for m in moves {
+        match m.color {
+            Color::White => tokens.push(format!("{}.", number)),
+            Color::Black if after_comment => tokens.push(format!("{}...", number)),
+            Color::Black => {}
+        }
+        tokens.push(m.san.clone());
+        after_comment = false;
+        if let Some(nag) = m.nag {
+            tokens.push(format!("${}", nag));
+        }
+        if let Some(comment) = &m.comment {
+            tokens.extend(format!("{{{}}}", comment).split(' ').map(String::from));
+            after_comment = true;
+        }
+        if m.color == Color::Black {
+            number += 1;
+        }
+    }
+    tokens.push(result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+    pgn
+}
+
+/// Writes the game played from `start` with `moves`, which have to be legal.
+pub fn game(
+    start: &Board,
+    moves: &[Move],
+    result: &str,
+    tags: &[(&str, &str)],
+) -> Result<String, Error> {
+    let mut board = start.clone();
+    let mut pgn_moves = vec![];
+    for m in moves {
+        pgn_moves.push(PgnMove {
+            color: board.side_to_move(),
+            san: board.san(*m)?,
+            nag: None,
+            comment: None,
+        });
+        board.make_move(*m)?;
+    }
+    Ok(write(&start.to_fen(), &pgn_moves, result, tags))
+}
@@ -0,0 +1,151 @@
+//! Drawing the board as text for terminals.
+
+use crate::{Board, BoardState, Color, File, Piece, Position, Rank};
+
+/// The characters pieces are drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyphs {
+    /// FEN letters, uppercase for white, for terminals without chess symbols.
+    Ascii,
+    /// The Unicode chess symbols.
+    Unicode,
+}
+
+const RESET: &str = "\x1b[0m";
+const LIGHT_SQUARE: &str = "\x1b[48;5;180m";
+const DARK_SQUARE: &str = "\x1b[48;5;137m";
+const LAST_MOVE_SQUARE: &str = "\x1b[48;5;143m";
+const CHECK_SQUARE: &str = "\x1b[48;5;160m";
+const WHITE_PIECE: &str = "\x1b[1;97m";
+const BLACK_PIECE: &str = "\x1b[1;30m";
+
+/// How to draw a board. Each square is three characters wide. Without ANSI colours the last
+/// move's squares are marked with brackets, like `[P]`, and a king in check with parentheses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Renderer {
+    /// The side at the bottom of the board.
+    pub perspective: Color,
+    /// Whether to label the ranks and files.
+    pub coordinates: bool,
+    pub glyphs: Glyphs,
+    pub highlight_last_move: bool,
+    pub highlight_check: bool,
+    /// Whether to colour the squares and pieces with ANSI escape codes.
+    pub ansi: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer {
+            perspective: Color::White,
+            coordinates: true,
+            glyphs: Glyphs::Unicode,
+            highlight_last_move: true,
+            highlight_check: true,
+            ansi: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    None,
+    LastMove,
+    Check,
+}
+
+impl Renderer {
+    fn glyph(&self, piece: Piece, color: Color) -> char {
+        match self.glyphs {
+            Glyphs::Ascii => piece.to_fen_char(color),
+            Glyphs::Unicode => match (color, piece) {
+                (Color::White, Piece::Pawn) => '♙',
+                (Color::White, Piece::Knight) => '♘',
+                (Color::White, Piece::Bishop) => '♗',
+                (Color::White, Piece::Rook) => '♖',
+                (Color::White, Piece::Queen) => '♕',
+                (Color::White, Piece::King) => '♔',
+                (Color::Black, Piece::Pawn) => '♟',
+                (Color::Black, Piece::Knight) => '♞',
+                (Color::Black, Piece::Bishop) => '♝',
+                (Color::Black, Piece::Rook) => '♜',
+                (Color::Black, Piece::Queen) => '♛',
+                (Color::Black, Piece::King) => '♚',
+            },
+        }
+    }
+
+    fn highlight(&self, board: &Board, position: Position) -> Highlight {
+        let checked = match board.state {
+            BoardState::Check(color) | BoardState::Checkmate(color) => board.king(color),
+            _ => None,
+        };
+        if self.highlight_check && checked == Some(position) {
+            return Highlight::Check;
+        }
+        let moved = board
+            .last_move()
+            .is_some_and(|m| m.from == position || m.to == position);
+        if self.highlight_last_move && moved {
+            return Highlight::LastMove;
+        }
+        Highlight::None
+    }
+
+    fn square(&self, board: &Board, position: Position) -> String {
+        let troop = board.squares[&position.file][&position.rank].troop.as_ref();
+        let glyph = troop.map_or('.', |troop| self.glyph(troop.piece, troop.color));
+        let highlight = self.highlight(board, position);
+        if !self.ansi {
+            return match highlight {
+                Highlight::None => format!(" {} ", glyph),
+                Highlight::LastMove => format!("[{}]", glyph),
+                Highlight::Check => format!("({})", glyph),
+            };
+        }
+        let light = (position.file as u8 + position.rank as u8) % 2 == 1;
+        let background = match (highlight, light) {
+            (Highlight::Check, _) => CHECK_SQUARE,
+            (Highlight::LastMove, _) => LAST_MOVE_SQUARE,
+            (Highlight::None, true) => LIGHT_SQUARE,
+            (Highlight::None, false) => DARK_SQUARE,
+        };
+        let foreground = match troop.map(|troop| troop.color) {
+            Some(Color::White) => WHITE_PIECE,
+            Some(Color::Black) => BLACK_PIECE,
+            None => "",
+        };
+        // Colour shows the squares, so empty ones don't need a dot
+        let glyph = troop.map_or(' ', |_| glyph);
+        format!("{}{} {} {}", background, foreground, glyph, RESET)
+    }
+
+    /// Draws `board`, one line per rank, without a trailing newline.
+    pub fn render(&self, board: &Board) -> String {
+        let mut ranks: Vec<Rank> = (1..=8).map(|rank| Rank::try_from(rank).unwrap()).collect();
+        let mut files: Vec<File> = (1..=8).map(|file| File::try_from(file).unwrap()).collect();
+        match self.perspective {
+            Color::White => ranks.reverse(),
+            Color::Black => files.reverse(),
+        }
+        let mut lines = vec![];
+        for rank in ranks {
+            let mut line = String::new();
+            if self.coordinates {
+                line.push_str(&format!("{} ", rank as u8 + 1));
+            }
+            for file in &files {
+                line.push_str(&self.square(board, Position { file: *file, rank }));
+            }
+            lines.push(line);
+        }
+        if self.coordinates {
+            let labels: String = files
+                .iter()
+                .map(|file| format!(" {} ", (b'a' + *file as u8) as char))
+                .collect();
+            lines.push(format!("  {}", labels).trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
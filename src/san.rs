@@ -0,0 +1,116 @@
+//! Standard Algebraic Notation, the way moves are written in PGN and most chess books.
+
+use crate::{Board, BoardState, Error, Move, Piece, Rank};
+
+fn letter(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "",
+        Piece::Knight => "N",
+        Piece::Bishop => "B",
+        Piece::Rook => "R",
+        Piece::Queen => "Q",
+        Piece::King => "K",
+    }
+}
+
+impl Board {
+    /// Writes a legal move in SAN, like `Nbd7`, `exd6`, `O-O` or `e8=Q#`. A pawn reaching the
+    /// last rank without a promotion piece promotes to a queen, as in [`Board::make_move`].
+    pub fn san(&self, m: Move) -> Result<String, Error> {
+        let mut after = self.clone();
+        after.make_move(m)?;
+        let troop = self.get_square(&m.from).troop.clone().unwrap();
+        let suffix = match after.state {
+            BoardState::Checkmate(_) => "#",
+            BoardState::Check(_) => "+",
+            _ => "",
+        };
+
+        if let Some(castle) = self.castle(m.from, m.to) {
+            let castle = match castle.kingside() {
+                true => "O-O",
+                false => "O-O-O",
+            };
+            return Ok(format!("{}{}", castle, suffix));
+        }
+
+        let capture = self.get_square(&m.to).troop.is_some()
+            || (troop.piece == Piece::Pawn && m.from.file != m.to.file);
+        let mut san = String::from(letter(troop.piece));
+        if troop.piece == Piece::Pawn {
+            if capture {
+                san.push((b'a' + m.from.file as u8) as char);
+            }
+        } else {
+            // Name the starting file, or failing that the rank, or both, when another piece of
+            // the same kind could also go there
+            let rivals: Vec<Move> = self
+                .legal_moves()
+                .into_iter()
+                .filter(|other| {
+                    other.to == m.to
+                        && other.from != m.from
+                        && self
+                            .get_square(&other.from)
+                            .troop
+                            .as_ref()
+                            .is_some_and(|other| other.piece == troop.piece)
+                })
+                .collect();
+            if !rivals.is_empty() {
+                let same_file = rivals.iter().any(|other| other.from.file == m.from.file);
+                let same_rank = rivals.iter().any(|other| other.from.rank == m.from.rank);
+                if !same_file || same_rank {
+                    san.push((b'a' + m.from.file as u8) as char);
+                }
+                if same_file {
+                    san.push((b'1' + m.from.rank as u8) as char);
+                }
+            }
+        }
+        if capture {
+            san.push('x');
+        }
+        san.push_str(&m.to.to_string());
+        let promotes = troop.piece == Piece::Pawn && matches!(m.to.rank, Rank::One | Rank::Eight);
+        if promotes {
+            san.push('=');
+            san.push_str(letter(m.promotion.unwrap_or(Piece::Queen)));
+        }
+        san.push_str(suffix);
+        Ok(san)
+    }
+
+    /// Reads a legal move written in SAN. Check and annotation marks don't matter, and
+    /// castling may be written with zeros.
+    pub fn parse_san(&self, san: &str) -> Result<Move, Error> {
+        let normalize = |san: &str| -> String {
+            san.chars()
+                .filter(|c| !"+#!?=".contains(*c))
+                .map(|c| if c == '0' { 'O' } else { c })
+                .collect()
+        };
+        let wanted = normalize(san.trim());
+        self.legal_moves()
+            .into_iter()
+            .find(|m| {
+                self.san(*m)
+                    .is_ok_and(|candidate| normalize(&candidate) == wanted)
+            })
+            .ok_or(Error::SanParse)
+    }
+
+    /// Writes a line of moves starting from this position in SAN, stopping with an error at
+    /// the first illegal one.
+    pub fn san_line(&self, moves: &[Move]) -> Result<Vec<String>, Error> {
+        let mut board = self.clone();
+        moves
+            .iter()
+            .map(|m| {
+                let san = board.san(*m)?;
+                board.make_move(*m)?;
+                Ok(san)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{Board, Error};
+
+pub type GameId = u64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StoreError {
+    NotFound,
+    Io,
+    Serialization,
+}
+
+/// Persistence backend for games, so a server process can resume them after a restart.
+pub trait GameStore {
+    fn create(&mut self, board: &Board) -> Result<GameId, Error>;
+    fn load(&self, id: GameId) -> Result<Board, Error>;
+    fn save(&mut self, id: GameId, board: &Board) -> Result<(), Error>;
+    fn list(&self) -> Result<Vec<GameId>, Error>;
+    fn delete(&mut self, id: GameId) -> Result<(), Error>;
+}
+
+fn serialize(board: &Board) -> Result<String, Error> {
+    serde_json::to_string(board).map_err(|_| Error::Store(StoreError::Serialization))
+}
+
+fn deserialize(record: &str) -> Result<Board, Error> {
+    Board::from_json_checked(record).map_err(|_| Error::Store(StoreError::Serialization))
+}
+
+/// Keeps serialized games in memory. Nothing survives the process, but it's handy for tests
+/// and for servers that don't need durability.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    games: HashMap<GameId, String>,
+    next_id: GameId,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GameStore for MemoryStore {
+    fn create(&mut self, board: &Board) -> Result<GameId, Error> {
+        let id = self.next_id;
+        self.games.insert(id, serialize(board)?);
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    fn load(&self, id: GameId) -> Result<Board, Error> {
+        match self.games.get(&id) {
+            Some(record) => deserialize(record),
+            None => Err(Error::Store(StoreError::NotFound)),
+        }
+    }
+
+    fn save(&mut self, id: GameId, board: &Board) -> Result<(), Error> {
+        match self.games.get_mut(&id) {
+            Some(record) => {
+                *record = serialize(board)?;
+                Ok(())
+            }
+            None => Err(Error::Store(StoreError::NotFound)),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<GameId>, Error> {
+        let mut ids: Vec<GameId> = self.games.keys().copied().collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn delete(&mut self, id: GameId) -> Result<(), Error> {
+        match self.games.remove(&id) {
+            Some(_) => Ok(()),
+            None => Err(Error::Store(StoreError::NotFound)),
+        }
+    }
+}
+
+/// Stores each game as `<id>.json` inside a directory. The next id is kept in a `next_id` file
+/// next to them, so ids of deleted games are never handed out again.
+#[derive(Debug)]
+pub struct FileStore {
+    directory: PathBuf,
+}
+
+impl FileStore {
+    /// Opens a store rooted at `directory`, creating the directory if it doesn't exist yet.
+    pub fn open(directory: impl Into<PathBuf>) -> Result<Self, Error> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory).map_err(|_| Error::Store(StoreError::Io))?;
+        Ok(FileStore { directory })
+    }
+
+    fn path(&self, id: GameId) -> PathBuf {
+        self.directory.join(format!("{}.json", id))
+    }
+
+    /// The id the next game gets. Directories written before the counter existed carry on
+    /// after their newest game.
+    fn next_id(&self) -> Result<GameId, Error> {
+        match std::fs::read_to_string(self.directory.join("next_id")) {
+            Ok(counter) => counter
+                .trim()
+                .parse()
+                .map_err(|_| Error::Store(StoreError::Serialization)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(self.list()?.last().map_or(0, |id| id + 1))
+            }
+            Err(_) => Err(Error::Store(StoreError::Io)),
+        }
+    }
+}
+
+impl GameStore for FileStore {
+    fn create(&mut self, board: &Board) -> Result<GameId, Error> {
+        let id = self.next_id()?;
+        let record = serialize(board)?;
+        // The counter moves on first, so a failed write can't leave the id free for reuse
+        std::fs::write(self.directory.join("next_id"), (id + 1).to_string())
+            .map_err(|_| Error::Store(StoreError::Io))?;
+        std::fs::write(self.path(id), record).map_err(|_| Error::Store(StoreError::Io))?;
+        Ok(id)
+    }
+
+    fn load(&self, id: GameId) -> Result<Board, Error> {
+        match std::fs::read_to_string(self.path(id)) {
+            Ok(record) => deserialize(&record),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Err(Error::Store(StoreError::NotFound))
+            }
+            Err(_) => Err(Error::Store(StoreError::Io)),
+        }
+    }
+
+    fn save(&mut self, id: GameId, board: &Board) -> Result<(), Error> {
+        if !self.path(id).exists() {
+            return Err(Error::Store(StoreError::NotFound));
+        }
+        std::fs::write(self.path(id), serialize(board)?).map_err(|_| Error::Store(StoreError::Io))
+    }
+
+    fn list(&self) -> Result<Vec<GameId>, Error> {
+        let entries =
+            std::fs::read_dir(&self.directory).map_err(|_| Error::Store(StoreError::Io))?;
+        let mut ids = vec![];
+        for entry in entries {
+            let path = entry.map_err(|_| Error::Store(StoreError::Io))?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(Ok(id)) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::parse::<GameId>)
+            {
+                ids.push(id);
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn delete(&mut self, id: GameId) -> Result<(), Error> {
+        match std::fs::remove_file(self.path(id)) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Err(Error::Store(StoreError::NotFound))
+            }
+            Err(_) => Err(Error::Store(StoreError::Io)),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Board diagrams as self-contained SVG, for reports and documentation.
+//!
+//! The drawings of the pieces on the board go in the file's `<defs>` and each piece is placed with
+//! `<use>`, so the file needs no images or fonts and looks the same in any viewer.
+
+use crate::{Board, BoardState, Color, File, Piece, Position, Rank};
+
+mod pieces;
+
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+const HIGHLIGHT: &str = "#cdd26a";
+const CHECK: &str = "#ff0000";
+const ARROW: &str = "#15781b";
+const MARGIN: &str = "#312e2b";
+const LABEL: &str = "#e0e0e0";
+
+/// How to draw a diagram. Coordinates go in a margin around the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagram {
+    /// The side at the bottom of the board.
+    pub perspective: Color,
+    pub coordinates: bool,
+    /// Squares to tint, like the ones a move came from and went to.
+    pub highlights: Vec<Position>,
+    /// Arrows from one square to another.
+    pub arrows: Vec<(Position, Position)>,
+    /// Whether to put a red glow under a king in check.
+    pub check: bool,
+    /// The width of a square in pixels.
+    pub square_size: u32,
+}
+
+impl Default for Diagram {
+    fn default() -> Self {
+        Diagram {
+            perspective: Color::White,
+            coordinates: true,
+            highlights: vec![],
+            arrows: vec![],
+            check: true,
+            square_size: 40,
+        }
+    }
+}
+
+impl Diagram {
+    fn margin(&self) -> u32 {
+        match self.coordinates {
+            true => self.square_size / 2,
+            false => 0,
+        }
+    }
+
+    /// The top left corner of `position`'s square.
+    fn corner(&self, position: Position) -> (u32, u32) {
+        let (column, row) = match self.perspective {
+            Color::White => (position.file as u32, 7 - position.rank as u32),
+            Color::Black => (7 - position.file as u32, position.rank as u32),
+        };
+        (
+            self.margin() + column * self.square_size,
+            self.margin() + row * self.square_size,
+        )
+    }
+
+    fn center(&self, position: Position) -> (u32, u32) {
+        let (x, y) = self.corner(position);
+        (x + self.square_size / 2, y + self.square_size / 2)
+    }
+
+    fn square(&self, position: Position, fill: &str) -> String {
+        let (x, y) = self.corner(position);
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+            x,
+            y,
+            fill,
+            size = self.square_size
+        )
+    }
+
+    /// Draws `board` as a standalone SVG document.
+    pub fn render(&self, board: &Board) -> String {
+        let size = self.square_size;
+        let total = 8 * size + 2 * self.margin();
+        let positions: Vec<Position> = (1..=8)
+            .flat_map(|rank| {
+                (1..=8).map(move |file| Position {
+                    file: File::try_from(file).unwrap(),
+                    rank: Rank::try_from(rank).unwrap(),
+                })
+            })
+            .collect();
+
+        let mut lines = vec![
+            format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{total}\" height=\"{total}\" \
+                 xmlns:xlink=\"http://www.w3.org/1999/xlink\" viewBox=\"0 0 {total} {total}\">",
+                total = total
+            ),
+            "<defs>".to_string(),
+            format!(
+                "<marker id=\"arrowhead\" viewBox=\"0 0 4 4\" refX=\"2\" refY=\"2\" \
+                 markerWidth=\"4\" markerHeight=\"4\" orient=\"auto\">\
+                 <path d=\"M0,0 L4,2 L0,4 z\" fill=\"{}\"/></marker>",
+                ARROW
+            ),
+            format!(
+                "<radialGradient id=\"check\"><stop offset=\"0%\" stop-color=\"{check}\"/>\
+                 <stop offset=\"100%\" stop-color=\"{check}\" stop-opacity=\"0\"/>\
+                 </radialGradient>",
+                check = CHECK
+            ),
+        ];
+        // Only the pieces on the board need drawing
+        let troops: Vec<(Piece, Color)> = positions
+            .iter()
+            .filter_map(|position| board.squares[&position.file][&position.rank].troop.as_ref())
+            .map(|troop| (troop.piece, troop.color))
+            .collect();
+        for color in [Color::White, Color::Black] {
+            for piece in [
+                Piece::Pawn,
+                Piece::Knight,
+                Piece::Bishop,
+                Piece::Rook,
+                Piece::Queen,
+                Piece::King,
+            ] {
+                if troops.contains(&(piece, color)) {
+                    lines.push(format!(
+                        "<symbol id=\"{}\" viewBox=\"0 0 45 45\">{}</symbol>",
+                        pieces::id(piece, color),
+                        pieces::drawing(piece, color)
+                    ));
+                }
+            }
+        }
+        lines.push("</defs>".to_string());
+        if self.coordinates {
+            lines.push(format!(
+                "<rect width=\"{total}\" height=\"{total}\" fill=\"{}\"/>",
+                MARGIN,
+                total = total
+            ));
+        }
+
+        for position in &positions {
+            let light = (position.file as u8 + position.rank as u8) % 2 == 1;
+            let fill = if light { LIGHT_SQUARE } else { DARK_SQUARE };
+            lines.push(self.square(*position, fill));
+        }
+        for position in &self.highlights {
+            lines.push(
+                self.square(*position, HIGHLIGHT)
+                    .replace("/>", " opacity=\"0.8\"/>"),
+            );
+        }
+        let checked = match board.state {
+            BoardState::Check(color) | BoardState::Checkmate(color) => board.king(color),
+            _ => None,
+        };
+        if let Some(king) = checked.filter(|_| self.check) {
+            lines.push(self.square(king, "url(#check)"));
+        }
+
+        if self.coordinates {
+            let font = size / 3;
+            for index in 0..8 {
+                let position = Position {
+                    file: File::try_from(index + 1).unwrap(),
+                    rank: Rank::try_from(index + 1).unwrap(),
+                };
+                let (x, y) = self.center(position);
+                lines.push(format!(
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" text-anchor=\"middle\" \
+                     dominant-baseline=\"central\" font-family=\"sans-serif\">{}</text>",
+                    x,
+                    total - self.margin() / 2,
+                    font,
+                    LABEL,
+                    (b'a' + index) as char
+                ));
+                lines.push(format!(
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" text-anchor=\"middle\" \
+                     dominant-baseline=\"central\" font-family=\"sans-serif\">{}</text>",
+                    self.margin() / 2,
+                    y,
+                    font,
+                    LABEL,
+                    index + 1
+                ));
+            }
+        }
+
+        for position in &positions {
+            let Some(troop) = &board.squares[&position.file][&position.rank].troop else {
+                continue;
+            };
+            let (x, y) = self.corner(*position);
+            lines.push(format!(
+                "<use xlink:href=\"#{}\" x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\"/>",
+                pieces::id(troop.piece, troop.color),
+                x,
+                y,
+                size = size
+            ));
+        }
+
+        for (from, to) in &self.arrows {
+            let (x1, y1) = self.center(*from);
+            let (x2, y2) = self.center(*to);
+            lines.push(format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" \
+                 stroke-linecap=\"round\" opacity=\"0.8\" marker-end=\"url(#arrowhead)\"/>",
+                x1,
+                y1,
+                x2,
+                y2,
+                ARROW,
+                size / 5
+            ));
+        }
+
+        lines.push("</svg>".to_string());
+        let mut svg = lines.join("\n");
+        svg.push('\n');
+        svg
+    }
+}
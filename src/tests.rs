@@ -1,59 +1,29 @@
-use crate::*;
-
+pub mod annotate;
+pub mod antichess;
+pub mod atomic;
+pub mod attacks;
+pub mod book;
+pub mod builder;
 pub mod check;
+pub mod chess960;
+pub mod client;
+pub mod crazyhouse;
+pub mod engine;
+pub mod eval;
+pub mod fen;
+pub mod helpers;
+pub mod horde;
+pub mod legal;
 pub mod movement;
-
-// #[test]
-// fn checkmate() {
-//     let mut board = Board::default();
-//     board
-//         .move_troop(
-//             Position {
-//                 file: File::F,
-//                 rank: Rank::Two,
-//             },
-//             Position {
-//                 file: File::F,
-//                 rank: Rank::Three,
-//             },
-//         )
-//         .unwrap();
-//     board
-//         .move_troop(
-//             Position {
-//                 file: File::E,
-//                 rank: Rank::Seven,
-//             },
-//             Position {
-//                 file: File::E,
-//                 rank: Rank::Six,
-//             },
-//         )
-//         .unwrap();
-//     board
-//         .move_troop(
-//             Position {
-//                 file: File::G,
-//                 rank: Rank::Two,
-//             },
-//             Position {
-//                 file: File::G,
-//                 rank: Rank::Four,
-//             },
-//         )
-//         .unwrap();
-//     board
-//         .move_troop(
-//             Position {
-//                 file: File::D,
-//                 rank: Rank::Eight,
-//             },
-//             Position {
-//                 file: File::H,
-//                 rank: Rank::Four,
-//             },
-//         )
-//         .unwrap();
-//
-//     assert_eq!(board.state, BoardState::Checkmate(Color::White));
-// }
+pub mod render;
+pub mod san;
+#[cfg(feature = "serde")]
+pub mod store;
+pub mod svg;
+pub mod uci;
+pub mod validate;
+pub mod variant;
+#[cfg(feature = "serde")]
+pub mod wire;
+pub mod xboard;
+pub mod zobrist;
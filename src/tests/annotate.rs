@@ -0,0 +1,61 @@
+use crate::annotate::*;
+use crate::*;
+
+fn moves(moves: &[&str]) -> Vec<Move> {
+    moves.iter().map(|m| m.parse().unwrap()).collect()
+}
+
+#[test]
+fn scholars_mate() {
+    let game = moves(&["e2e4", "e7e5", "d1h5", "b8c6", "f1c4", "g8f6", "h5f7"]);
+    let annotation = annotate(&Board::default(), &game, 2, Thresholds::default()).unwrap();
+    assert_eq!(annotation.result, "1-0");
+
+    let blunder = &annotation.moves[5];
+    assert_eq!(blunder.san, "Nf6");
+    assert_eq!(blunder.color, Color::Black);
+    assert_eq!(blunder.judgement, Some(Judgement::Blunder));
+    assert!(!blunder.best_line.is_empty());
+    let mate = &annotation.moves[6];
+    assert_eq!(mate.san, "Qxf7#");
+    assert_eq!((mate.loss, mate.judgement), (0, None));
+
+    assert_eq!(annotation.black.blunders, 1);
+    assert_eq!(annotation.white.blunders, 0);
+    assert!(annotation.white.accuracy > annotation.black.accuracy);
+    assert!(annotation.black.average_loss >= 300.0 / 4.0);
+
+    let pgn = annotation.pgn(&[
+        ("White", "Alice"),
+        ("Black", "Bob"),
+        ("Annotator", "rust-chess"),
+    ]);
+    assert!(pgn.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n"));
+    assert!(pgn.contains("[White \"Alice\"]\n[Black \"Bob\"]\n[Result \"1-0\"]\n"));
+    assert!(pgn.contains("[Annotator \"rust-chess\"]\n\n1. e4 e5 2. Qh5"));
+    assert!(pgn.contains(" 3. Bc4 Nf6 $4 {Blunder. Best was "));
+    assert!(pgn.trim_end().ends_with("4. Qxf7# 1-0"));
+    assert!(pgn.lines().all(|line| line.len() <= 80));
+}
+
+#[test]
+fn from_position() {
+    // Black to move trades queens
+    let start = Board::from_fen("4k3/8/8/3q4/8/8/3Q4/4K3 b - - 0 1").unwrap();
+    let annotation = annotate(&start, &moves(&["d5d2"]), 2, Thresholds::default()).unwrap();
+    assert_eq!(annotation.result, "*");
+    assert_eq!(annotation.moves[0].judgement, None);
+    let pgn = annotation.pgn(&[]);
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/3q4/8/8/3Q4/4K3 b - - 0 1\"]\n"));
+    assert!(pgn.contains("\n1... Qxd2+ *"));
+
+    assert!(annotate(&start, &moves(&["e2e4"]), 1, Thresholds::default()).is_err());
+}
+
+#[test]
+fn judgement_symbols() {
+    assert_eq!(Judgement::Inaccuracy.symbol(), "?!");
+    assert_eq!(Judgement::Mistake.nag(), 2);
+    assert_eq!(Judgement::Blunder.symbol(), "??");
+    assert_eq!(Judgement::Blunder.nag(), 4);
+}
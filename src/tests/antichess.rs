@@ -0,0 +1,114 @@
+use crate::engine::{search, Limits};
+use crate::eval::{breakdown, EvalParams};
+use crate::tests::helpers::{from_fen_as, perft};
+use crate::*;
+
+fn moves(board: &Board) -> Vec<String> {
+    board.legal_moves().iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn antichess_perft() {
+    let board = Variant::Antichess.starting_position();
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"
+    );
+    assert_eq!(perft(&board, 1), 20);
+    assert_eq!(perft(&board, 2), 400);
+    assert_eq!(perft(&board, 3), 8067);
+}
+
+#[test]
+fn captures_are_compulsory() {
+    let mut board = Variant::Antichess.starting_position();
+    board.make_move("e2e3".parse().unwrap()).unwrap();
+    board.make_move("b7b5".parse().unwrap()).unwrap();
+    assert_eq!(moves(&board), ["f1b5"]);
+    assert_eq!(
+        board.make_move("d2d4".parse().unwrap()),
+        Err(Error::Move(MoveError::Other))
+    );
+
+    // Any capture will do, en passant included
+    let board = from_fen_as("4k3/8/8/3pP3/8/8/K7/R6n w - d6 0 1", Variant::Antichess);
+    assert_eq!(moves(&board), ["a1h1", "e5d6"]);
+}
+
+#[test]
+fn kings_are_ordinary_pieces() {
+    // Nothing is in check, kings can be taken, and there's no castling
+    let board = from_fen_as("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", Variant::Antichess);
+    assert_eq!(board.state, BoardState::ToMove(Color::White));
+    assert!(!moves(&board).contains(&"e1g1".to_string()));
+
+    let mut board = from_fen_as("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1", Variant::Antichess);
+    assert_eq!(moves(&board), ["e1e8"]);
+    board.make_move("e1e8".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "4R3/8/8/8/8/8/8/6K1 b - - 0 1");
+
+    let mut board = from_fen_as("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", Variant::Antichess);
+    assert!(moves(&board).contains(&"a7a8k".to_string()));
+    board.make_move("a7a8k".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "K3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+
+    let mut board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.make_move("a7a8k".parse().unwrap()),
+        Err(Error::Move(MoveError::Other))
+    );
+}
+
+#[test]
+fn several_kings() {
+    let fen = "K3k3/8/8/8/8/8/8/4K3 b - - 0 1";
+    let board = from_fen_as(fen, Variant::Antichess);
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board.validate(), Ok(()));
+    assert_eq!(Board::from_fen(fen).unwrap_err(), Error::FenParse);
+
+    let builder = || {
+        BoardBuilder::new()
+            .place(Piece::King, Color::White, "a8".parse().unwrap())
+            .place(Piece::King, Color::White, "e1".parse().unwrap())
+            .place(Piece::King, Color::Black, "e8".parse().unwrap())
+            .side_to_move(Color::Black)
+    };
+    let board = builder().variant(Variant::Antichess).build().unwrap();
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board.validate(), Ok(()));
+    assert_eq!(
+        builder().build().unwrap_err(),
+        Error::Setup(SetupError::KingCount(Color::White))
+    );
+}
+
+#[test]
+fn losing_everything_wins() {
+    let mut board = from_fen_as("8/8/8/8/8/8/3k4/4K3 w - - 0 1", Variant::Antichess);
+    board.make_move("e1d2".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantWin(Color::Black));
+    assert!(board.legal_moves().is_empty());
+    assert_eq!(
+        board.make_move("d2d3".parse().unwrap()),
+        Err(Error::Move(MoveError::GameOver))
+    );
+
+    // So does being stalemated
+    let board = from_fen_as("4k3/8/8/8/8/p7/P7/8 w - - 0 1", Variant::Antichess);
+    assert_eq!(board.state, BoardState::VariantWin(Color::White));
+}
+
+#[test]
+fn engine_sheds_material() {
+    let board = from_fen_as("7k/8/2p5/3p4/p7/8/7P/3Q4 w - - 0 1", Variant::Antichess);
+    let breakdown = breakdown(&board, &EvalParams::default());
+    // White has more to lose, which is the worse place to be
+    assert!(breakdown.material.net() < 0);
+    assert_eq!(breakdown.king_safety.net(), 0);
+
+    // Taking on a4 keeps the queen, taking on d5 forces black to take it
+    assert_eq!(moves(&board), ["d1a4", "d1d5"]);
+    let result = search(&board, Limits::depth(3)).unwrap();
+    assert_eq!(result.best_move.unwrap().to_string(), "d1d5");
+}
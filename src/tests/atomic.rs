@@ -0,0 +1,60 @@
+use crate::tests::helpers::{from_fen_as, perft};
+use crate::*;
+
+#[test]
+fn explosions() {
+    // Taking on d5 blows up the knights and bishop around it, but not the pawns
+    let mut board = from_fen_as("4k3/8/2nbn3/2pp4/4N3/8/8/4K3 w - - 0 1", Variant::Atomic);
+    board
+        .move_troop("e4".parse().unwrap(), "d6".parse().unwrap())
+        .unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/2pp4/8/8/8/4K3 b - - 0 1");
+
+    // Exploding the king next to the capture wins
+    let mut board = from_fen_as("3qk3/8/8/8/8/8/8/3RK3 w - - 0 1", Variant::Atomic);
+    board.make_move("d1d8".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantLoss(Color::Black));
+    assert!(board.legal_moves().is_empty());
+}
+
+#[test]
+fn king_rules() {
+    // Kings can't capture, and may stand next to each other
+    let board = from_fen_as("8/8/8/3k4/8/3K4/3p4/8 w - - 0 1", Variant::Atomic);
+    let moves: Vec<String> = board.legal_moves().iter().map(|m| m.to_string()).collect();
+    assert!(!moves.contains(&"d3d2".to_string()));
+    assert!(moves.contains(&"d3d4".to_string()));
+
+    // Touching kings can't be checked
+    let board = from_fen_as("8/8/8/8/3k4/3K4/8/3r4 w - - 0 1", Variant::Atomic);
+    assert_eq!(board.state, BoardState::ToMove(Color::White));
+
+    // A capture can't blow up the mover's own king
+    let board = from_fen_as("4k3/8/8/8/8/8/3p4/3QK3 w - - 0 1", Variant::Atomic);
+    assert!(!board.legal_moves().contains(&"d1d2".parse().unwrap()));
+
+    // Blowing up the enemy king wins even out of check
+    let board = from_fen_as("8/8/8/8/8/3rk3/8/3QK2r w - - 0 1", Variant::Atomic);
+    assert_eq!(board.state, BoardState::Check(Color::White));
+    assert!(board.legal_moves().contains(&"d1d3".parse().unwrap()));
+}
+
+#[test]
+fn perft_suite() {
+    let board = Variant::Atomic.starting_position();
+    assert_eq!(perft(&board, 1), 20);
+    assert_eq!(perft(&board, 2), 400);
+    assert_eq!(perft(&board, 3), 8902);
+    let board = from_fen_as(
+        "rn2kb1r/1pp1p2p/p2q1pp1/3P4/2P3b1/4PN2/PP3PPP/R2QKB1R b KQkq - 0 1",
+        Variant::Atomic,
+    );
+    assert_eq!(perft(&board, 1), 40);
+    assert_eq!(perft(&board, 2), 1238);
+    let board = from_fen_as(
+        "rn1qkb1r/p5pp/2p5/3p4/N3P3/5P2/PPP4P/R1BQK3 w Qkq - 0 1",
+        Variant::Atomic,
+    );
+    assert_eq!(perft(&board, 1), 28);
+    assert_eq!(perft(&board, 2), 833);
+}
@@ -0,0 +1,52 @@
+use crate::*;
+
+fn pos(s: &str) -> Position {
+    s.parse().unwrap()
+}
+
+#[test]
+fn default_attack_maps() {
+    let board = Board::default();
+    let white = board.attack_map(Color::White);
+    // Ranks two and three, plus every back rank square but the rook corners
+    assert_eq!(white.count(), 22);
+    assert!(white.contains(pos("f3")));
+    assert!(!white.contains(pos("e4")));
+    assert!(!white.contains(pos("a1")));
+    assert_eq!(board.attack_map(Color::Black).count(), 22);
+}
+
+#[test]
+fn attackers_of() {
+    let board = Board::from_fen("4k3/8/2n5/3p4/4P3/5B2/8/R3K3 w - - 0 1").unwrap();
+    let mut attackers: Vec<String> = board
+        .attackers_of(pos("e4"))
+        .iter()
+        .map(|troop| troop.position.to_string())
+        .collect();
+    attackers.sort();
+    assert_eq!(attackers, ["d5", "f3"]);
+
+    let attackers = board.attackers_of(pos("d5"));
+    assert_eq!(attackers.len(), 1);
+    assert_eq!(attackers[0].position, pos("e4"));
+}
+
+#[test]
+fn sliders_are_blocked() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R1n1K3 w - - 0 1").unwrap();
+    assert!(board.is_attacked(pos("c1"), Color::White));
+    assert!(!board
+        .attacks(board.get_square(&pos("a1")).troop.as_ref().unwrap())
+        .contains(pos("d1")));
+    assert!(board.is_attacked(pos("e2"), Color::Black));
+}
+
+#[test]
+fn pawns_attack_diagonally() {
+    let board = Board::from_fen("4k3/8/8/3p4/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(board.is_attacked(pos("c4"), Color::Black));
+    assert!(board.is_attacked(pos("e4"), Color::Black));
+    assert!(!board.is_attacked(pos("d4"), Color::Black));
+    assert!(!board.is_attacked(pos("c6"), Color::Black));
+}
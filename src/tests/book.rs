@@ -0,0 +1,193 @@
+use crate::book::*;
+use crate::*;
+
+fn encode(m: &str) -> u16 {
+    let m: Move = m.parse().unwrap();
+    let promotion = match m.promotion {
+        Some(Piece::Knight) => 1,
+        Some(Piece::Bishop) => 2,
+        Some(Piece::Rook) => 3,
+        Some(Piece::Queen) => 4,
+        _ => 0,
+    };
+    promotion << 12 | (m.from.index() as u16) << 6 | m.to.index() as u16
+}
+
+fn entry(key: u64, m: &str, weight: u16) -> Vec<u8> {
+    let mut bytes = key.to_be_bytes().to_vec();
+    bytes.extend(encode(m).to_be_bytes());
+    bytes.extend(weight.to_be_bytes());
+    bytes.extend(0u32.to_be_bytes());
+    bytes
+}
+
+fn book(entries: &[(&Board, &str, u16)]) -> Book {
+    let keys = PolyglotKeys::default();
+    let bytes: Vec<u8> = entries
+        .iter()
+        .flat_map(|(board, m, weight)| entry(keys.key(board), m, *weight))
+        .collect();
+    Book::from_bytes(&bytes, keys).unwrap()
+}
+
+#[test]
+fn key_layout() {
+    // Find which numbers the key is built from by setting one at a time
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let used: Vec<usize> = (0..781)
+        .filter(|index| {
+            let mut only = vec![0; 781];
+            only[*index] = 1;
+            PolyglotKeys::new(only).unwrap().key(&board) == 1
+        })
+        .collect();
+    // The black king on e8 is kind 10, the white king on e1 kind 11, and white is to move
+    assert_eq!(used, [64 * 10 + 60, 64 * 11 + 4, 780]);
+
+    let board = Board::default();
+    let used = (768..772).filter(|index| {
+        let mut only = vec![0; 781];
+        only[*index] = 1;
+        PolyglotKeys::new(only).unwrap().key(&board) == 1
+    });
+    assert_eq!(used.count(), 4);
+}
+
+#[test]
+fn published_keys() {
+    // The test positions from the Polyglot book format specification
+    let keys = PolyglotKeys::default();
+    let cases: [(&str, u64); 9] = [
+        ("", 0x463b96181691fc9c),
+        ("e2e4", 0x823c9b50fd114196),
+        ("e2e4 d7d5", 0x0756b94461c50fb0),
+        ("e2e4 d7d5 e4e5", 0x662fafb965db29d4),
+        ("e2e4 d7d5 e4e5 f7f5", 0x22a48b5a8e47ff78),
+        ("e2e4 d7d5 e4e5 f7f5 e1e2", 0x652a607ca3f242c1),
+        ("e2e4 d7d5 e4e5 f7f5 e1e2 e8f7", 0x00fdd303c946bdd9),
+        ("a2a4 b7b5 h2h4 b5b4 c2c4", 0x3c8123ea7b067637),
+        ("a2a4 b7b5 h2h4 b5b4 c2c4 b4c3 a1a3", 0x5c3f9b829b279560),
+    ];
+    for (moves, expected) in cases {
+        let mut board = Board::default();
+        for m in moves.split_whitespace() {
+            board.make_move(m.parse().unwrap()).unwrap();
+        }
+        assert_eq!(keys.key(&board), expected, "after {:?}", moves);
+    }
+}
+
+#[test]
+fn en_passant_only_counts_when_capturable() {
+    let keys = PolyglotKeys::default();
+    let key = |fen: &str| keys.key(&Board::from_fen(fen).unwrap());
+    // No black pawn next to e4
+    assert_eq!(
+        key("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1"),
+        key("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1")
+    );
+    assert_ne!(
+        key("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1"),
+        key("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1")
+    );
+}
+
+#[test]
+fn parse_keys() {
+    let text = (0..781)
+        .map(|index| format!("0x{:016X}ULL", index))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    assert!(PolyglotKeys::parse(&format!("const u64 Random64[781] = {{ {} }};", text)).is_ok());
+    assert_eq!(
+        PolyglotKeys::parse("0x1, 0x2"),
+        Err(Error::Book(BookError::Keys))
+    );
+    assert_eq!(
+        PolyglotKeys::load("/nonexistent/pg_key.c"),
+        Err(Error::Book(BookError::Io))
+    );
+}
+
+#[test]
+fn moves_by_weight() {
+    let start = Board::default();
+    let other = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let book = book(&[
+        (&start, "d2d4", 5),
+        (&other, "e1e2", 1),
+        (&start, "e2e4", 10),
+        // Not legal here, so skipped
+        (&start, "e2e5", 50),
+    ]);
+    assert_eq!(book.len(), 4);
+    assert_eq!(
+        book.moves(&start),
+        [
+            BookMove {
+                m: "e2e4".parse().unwrap(),
+                weight: 10
+            },
+            BookMove {
+                m: "d2d4".parse().unwrap(),
+                weight: 5
+            },
+        ]
+    );
+    assert_eq!(
+        book.pick(&start, Selection::Best),
+        Some("e2e4".parse().unwrap())
+    );
+    assert_eq!(book.weighted_pick(&start, 9), Some("e2e4".parse().unwrap()));
+    assert_eq!(
+        book.weighted_pick(&start, 10),
+        Some("d2d4".parse().unwrap())
+    );
+    assert_eq!(
+        book.weighted_pick(&start, 15),
+        Some("e2e4".parse().unwrap())
+    );
+    let random = book.pick(&start, Selection::WeightedRandom);
+    assert!(book
+        .moves(&start)
+        .iter()
+        .any(|book_move| Some(book_move.m) == random));
+
+    let unknown = Board::from_fen("4k3/8/8/8/8/8/8/3K4 w - - 0 1").unwrap();
+    assert_eq!(book.moves(&unknown), []);
+    assert_eq!(book.pick(&unknown, Selection::WeightedRandom), None);
+}
+
+#[test]
+fn castling_and_promotion() {
+    let castling = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let promotion = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    // Polyglot writes castling as the king taking its rook
+    let book = book(&[
+        (&castling, "e1h1", 2),
+        (&castling, "e1a1", 1),
+        (&promotion, "b7b8n", 1),
+    ]);
+    let moves: Vec<Move> = book
+        .moves(&castling)
+        .iter()
+        .map(|book_move| book_move.m)
+        .collect();
+    assert_eq!(moves, ["e1g1".parse().unwrap(), "e1c1".parse().unwrap()]);
+    assert_eq!(
+        book.pick(&promotion, Selection::Best),
+        Some("b7b8n".parse().unwrap())
+    );
+}
+
+#[test]
+fn malformed_book() {
+    assert_eq!(
+        Book::from_bytes(&[0; 17], PolyglotKeys::default()).unwrap_err(),
+        Error::Book(BookError::Format)
+    );
+    assert_eq!(
+        Book::open("/nonexistent/book.bin", PolyglotKeys::default()).unwrap_err(),
+        Error::Book(BookError::Io)
+    );
+}
@@ -0,0 +1,125 @@
+use crate::*;
+
+fn pos(s: &str) -> Position {
+    s.parse().unwrap()
+}
+
+fn kings() -> BoardBuilder {
+    BoardBuilder::new()
+        .place(Piece::King, Color::White, pos("e1"))
+        .place(Piece::King, Color::Black, pos("e8"))
+}
+
+#[test]
+fn builds_position() {
+    let board = kings()
+        .place(Piece::Pawn, Color::White, pos("d5"))
+        .place(Piece::Pawn, Color::Black, pos("e5"))
+        .place(Piece::Rook, Color::Black, pos("a8"))
+        .side_to_move(Color::White)
+        .castling_rights(CastlingRights {
+            black_queenside: true,
+            ..Default::default()
+        })
+        .en_passant(Some(pos("e6")))
+        .build()
+        .unwrap();
+    assert_eq!(board.to_fen(), "r3k3/8/8/3Pp3/8/8/8/4K3 w q e6 0 1");
+    assert_eq!(board.state, BoardState::ToMove(Color::White));
+}
+
+#[test]
+fn detects_check() {
+    let board = kings()
+        .place(Piece::Queen, Color::White, pos("e4"))
+        .side_to_move(Color::Black)
+        .build()
+        .unwrap();
+    assert_eq!(board.state, BoardState::Check(Color::Black));
+}
+
+#[test]
+fn edits_existing_board() {
+    let board = BoardBuilder::from_board(&Board::default())
+        .remove(pos("d1"))
+        .build()
+        .unwrap();
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1"
+    );
+}
+
+#[test]
+fn king_count() {
+    assert_eq!(
+        BoardBuilder::new()
+            .place(Piece::King, Color::White, pos("e1"))
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::KingCount(Color::Black))
+    );
+    assert_eq!(
+        kings()
+            .place(Piece::King, Color::White, pos("a1"))
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::KingCount(Color::White))
+    );
+}
+
+#[test]
+fn pawn_on_back_rank() {
+    assert_eq!(
+        kings()
+            .place(Piece::Pawn, Color::Black, pos("c1"))
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::PawnOnBackRank(pos("c1")))
+    );
+}
+
+#[test]
+fn opponent_in_check() {
+    assert_eq!(
+        kings()
+            .place(Piece::Rook, Color::White, pos("a8"))
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::OpponentInCheck)
+    );
+}
+
+#[test]
+fn castling_rights_need_king_and_rook() {
+    assert_eq!(
+        kings()
+            .castling_rights(CastlingRights {
+                white_kingside: true,
+                ..Default::default()
+            })
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::CastlingRights)
+    );
+}
+
+#[test]
+fn en_passant_needs_double_push() {
+    assert_eq!(
+        kings()
+            .place(Piece::Pawn, Color::Black, pos("d5"))
+            .en_passant(Some(pos("d3")))
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::EnPassant)
+    );
+    assert_eq!(
+        kings()
+            .place(Piece::Pawn, Color::Black, pos("d5"))
+            .en_passant(Some(pos("e6")))
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::EnPassant)
+    );
+}
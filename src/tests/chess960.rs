@@ -0,0 +1,128 @@
+use crate::tests::helpers::perft;
+use crate::*;
+
+fn back_rank(board: &Board) -> String {
+    board.to_fen().split('/').next().unwrap().to_string()
+}
+
+#[test]
+fn starting_positions() {
+    assert_eq!(back_rank(&Board::chess960(0).unwrap()), "bbqnnrkr");
+    assert_eq!(back_rank(&Board::chess960(518).unwrap()), "rnbqkbnr");
+    assert_eq!(back_rank(&Board::chess960(959).unwrap()), "rkrnnqbb");
+    assert!(Board::chess960(960).is_none());
+
+    let mut ranks: Vec<String> = (0..960)
+        .map(|index| back_rank(&Board::chess960(index).unwrap()))
+        .collect();
+    ranks.sort();
+    ranks.dedup();
+    assert_eq!(ranks.len(), 960);
+
+    let board = Board::random_chess960();
+    assert!(board.is_chess960());
+    assert!(ranks.contains(&back_rank(&board)));
+}
+
+#[test]
+fn shredder_and_x_fen() {
+    let board = Board::chess960(518).unwrap();
+    assert_eq!(
+        board.to_shredder_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+    );
+    assert!(!Board::default().is_chess960());
+    assert!(Board::from_fen(&board.to_shredder_fen())
+        .unwrap()
+        .is_chess960());
+
+    // KQkq name the outermost rooks, so a right for an inner one needs its file
+    let fen = "rk1r3r/8/8/8/8/8/8/RK1R3R w Dd - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    assert!(board.is_chess960());
+    assert_eq!(board.castling_rooks().white_kingside, File::D);
+    assert_eq!(board.to_fen(), fen);
+    let outer = Board::from_fen("rk1r3r/8/8/8/8/8/8/RK1R3R w KQkq - 0 1").unwrap();
+    assert_eq!(outer.castling_rooks().white_kingside, File::H);
+    assert_eq!(
+        outer.to_shredder_fen(),
+        "rk1r3r/8/8/8/8/8/8/RK1R3R w HAha - 0 1"
+    );
+}
+
+#[test]
+fn castling() {
+    // The king stays on g1 and only the rook moves
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/6KR w H - 0 1").unwrap();
+    assert_eq!(board.san("g1h1".parse().unwrap()).unwrap(), "O-O");
+    board.make_move("g1h1".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 0 1");
+
+    // The king and rook swap past each other
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/1KR5 w C - 0 1").unwrap();
+    board.make_move("b1c1".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 0 1");
+
+    // Queenside from b1 leaves the king on c1 and the rook on d1
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/RK6 w A - 0 1").unwrap();
+    assert_eq!(board.parse_san("O-O-O").unwrap(), "b1a1".parse().unwrap());
+    board.make_move("b1a1".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/2KR4 b - - 0 1");
+
+    // The rook on b1 hides a check from a1 that castling would uncover
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/rR1K4 w B - 0 1").unwrap();
+    assert!(!board.legal_moves().contains(&"d1b1".parse().unwrap()));
+
+    // A piece between the rook and its destination blocks castling
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/RBK5 w A - 0 1").unwrap();
+    assert!(!board.legal_moves().contains(&"c1a1".parse().unwrap()));
+}
+
+#[test]
+fn standard_encoding() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    assert!(board.legal_moves().contains(&"e1g1".parse().unwrap()));
+    board.set_chess960(true);
+    assert!(!board.legal_moves().contains(&"e1g1".parse().unwrap()));
+    assert!(board.legal_moves().contains(&"e1h1".parse().unwrap()));
+    board.make_move("e1a1".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/2KR3R b - - 0 1");
+}
+
+#[test]
+fn builder() {
+    let rooks = CastlingRooks {
+        white_kingside: File::G,
+        ..Default::default()
+    };
+    let builder = BoardBuilder::new()
+        .place(Piece::King, Color::White, "b1".parse().unwrap())
+        .place(Piece::King, Color::Black, "e8".parse().unwrap())
+        .place(Piece::Rook, Color::White, "g1".parse().unwrap())
+        .castling_rights(CastlingRights {
+            white_kingside: true,
+            ..Default::default()
+        })
+        .castling_rooks(rooks);
+    assert_eq!(
+        builder.clone().build().unwrap_err(),
+        Error::Setup(SetupError::CastlingRights)
+    );
+    let board = builder.chess960(true).build().unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/1K4R1 w K - 0 1");
+}
+
+#[test]
+fn perft_positions() {
+    let board =
+        Board::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")
+            .unwrap();
+    assert_eq!(perft(&board, 1), 21);
+    assert_eq!(perft(&board, 2), 528);
+    assert_eq!(perft(&board, 3), 12189);
+    let board =
+        Board::from_fen("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9").unwrap();
+    assert_eq!(perft(&board, 1), 21);
+    assert_eq!(perft(&board, 2), 807);
+    assert_eq!(perft(&board, 3), 18002);
+}
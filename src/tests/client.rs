@@ -0,0 +1,92 @@
+use crate::client::{run, Options};
+use crate::engine::Limits;
+use crate::*;
+
+fn script(input: &str, engine: Option<Color>) -> Vec<String> {
+    let mut output = vec![];
+    let options = Options {
+        engine,
+        limits: Limits::depth(1),
+        ..Default::default()
+    };
+    run(input.as_bytes(), &mut output, options).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn board_with_coordinates() {
+    let lines = script("e4\n", None);
+    assert_eq!(lines[1], "8  r  n  b  q  k  b  n  r ");
+    assert_eq!(lines[9], "   a  b  c  d  e  f  g  h");
+    assert_eq!(lines[10], "White to move");
+    // The pawn's old and new squares are marked
+    assert_eq!(lines[15], "4  .  .  .  . [P] .  .  . ");
+    assert_eq!(lines[17], "2  P  P  P  P [.] P  P  P ");
+    assert_eq!(lines[20], "Black to move");
+}
+
+#[test]
+fn san_and_coordinates() {
+    let lines = script("e2e4\nNf6\nNf3\nxx\nNg4\ngame\n", None);
+    assert!(lines.contains(&"Illegal move: xx".to_string()));
+    assert!(lines.contains(&"Illegal move: game".to_string()));
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.ends_with("to move"))
+            .count(),
+        5
+    );
+}
+
+#[test]
+fn against_engine() {
+    let lines = script("e4\nundo\n", Some(Color::Black));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("rust-chess plays ")));
+    // Taking back a move against the engine takes back its reply as well
+    assert_eq!(
+        &lines[lines.len() - 10..][..2],
+        ["8  r  n  b  q  k  b  n  r ", "7  p  p  p  p  p  p  p  p "]
+    );
+    assert_eq!(lines.last().unwrap(), "White to move");
+
+    let lines = script("", Some(Color::White));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("rust-chess plays ")));
+    assert_eq!(lines.last().unwrap(), "Black to move");
+}
+
+#[test]
+fn resign_and_draw() {
+    let lines = script("e4\nresign\ne5\n", None);
+    assert!(lines.contains(&"1-0 (Black resigns)".to_string()));
+    assert!(lines.last().unwrap().starts_with("The game is over."));
+
+    let lines = script("draw\ne4\ndraw\n", None);
+    assert!(lines.contains(
+        &"White offers a draw. Black can type draw to accept, or move to decline.".to_string()
+    ));
+    assert_eq!(lines.last().unwrap(), "1/2-1/2 (Draw agreed)");
+
+    // Declined by moving
+    let lines = script("draw\ne4\ne5\ndraw\n", None);
+    assert!(lines.last().unwrap().starts_with("White offers a draw."));
+}
+
+#[test]
+fn save_pgn() {
+    let path = std::env::temp_dir().join(format!("rust-chess-client-{}.pgn", std::process::id()));
+    let lines = script(&format!("f3\ne5\ng4\nQh4\nsave {}\n", path.display()), None);
+    assert!(lines.contains(&"0-1 (Black mates)".to_string()));
+    let pgn = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(pgn.contains("[Result \"0-1\"]"));
+    assert!(pgn.ends_with("1. f3 e5 2. g4 Qh4# 0-1\n"));
+}
@@ -0,0 +1,97 @@
+use crate::*;
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for m in moves {
+        board.make_move(m.parse().unwrap()).unwrap();
+    }
+}
+
+#[test]
+fn captures_fill_pockets() {
+    let mut board = Variant::Crazyhouse.starting_position();
+    assert!(board
+        .to_fen()
+        .starts_with("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w"));
+    play(&mut board, &["e2e4", "d7d5", "e4d5", "d8d5"]);
+    assert_eq!(board.pocket(Color::White).count(Piece::Pawn), 1);
+    assert_eq!(board.pocket(Color::Black).count(Piece::Pawn), 1);
+    assert_eq!(
+        board.to_fen(),
+        "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR[Pp] w KQkq - 0 1"
+    );
+
+    // Standard games don't keep pockets
+    let mut board = Board::default();
+    play(&mut board, &["e2e4", "d7d5", "e4d5"]);
+    assert!(board.pocket(Color::White).is_empty());
+}
+
+#[test]
+fn promoted_pieces_revert() {
+    let mut board = Board::from_fen("4k3/1P6/8/8/8/8/7K/1r6[] w - - 0 1").unwrap();
+    assert_eq!(board.variant(), Variant::Crazyhouse);
+    play(&mut board, &["b7b8q"]);
+    assert!(board.is_promoted("b8".parse().unwrap()));
+    assert!(board.to_fen().starts_with("1Q~2k3/"));
+    play(&mut board, &["b1b8"]);
+    assert_eq!(board.pocket(Color::Black).count(Piece::Pawn), 1);
+    assert_eq!(board.pocket(Color::Black).count(Piece::Queen), 0);
+
+    let fen = "1Q~2k3/8/8/8/8/8/7K/1r6[Qn] b - - 0 1";
+    assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    assert!(Board::from_fen("~4k3/8/8/8/8/8/7K/8[] w - - 0 1").is_err());
+}
+
+#[test]
+fn drops() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3/Pn w - - 0 1").unwrap();
+    let drops = board.legal_drops();
+    assert_eq!(drops.len(), 62 - 14);
+    assert!(!drops
+        .iter()
+        .any(|drop| matches!(drop.to.rank, Rank::One | Rank::Eight)));
+    assert_eq!(
+        board.make_drop("N@e5".parse().unwrap()),
+        Err(Error::Move(MoveError::Other))
+    );
+    board.make_drop("@e5".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/4P3/8/8/8/4K3[n] b - - 0 1");
+    board.make_drop("N@d3".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::Check(Color::White));
+    assert_eq!("N@d3".parse::<DropMove>().unwrap().to_string(), "N@d3");
+
+    // In check a drop has to block
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1").unwrap();
+    let squares: Vec<String> = board
+        .legal_drops()
+        .iter()
+        .map(|drop| drop.to.to_string())
+        .collect();
+    assert_eq!(squares, ["b1", "c1", "d1"]);
+
+    // A back rank mate isn't one while a piece in hand can block it
+    let mut board = Board::from_fen("r5k1/5ppp/8/8/8/8/5PPP/6K1[N] b - - 0 1").unwrap();
+    play(&mut board, &["a8a1"]);
+    assert_eq!(board.state, BoardState::Check(Color::White));
+    let mut board = Board::from_fen("r5k1/5ppp/8/8/8/8/5PPP/6K1[] b - - 0 1").unwrap();
+    play(&mut board, &["a8a1"]);
+    assert_eq!(board.state, BoardState::Checkmate(Color::White));
+}
+
+#[test]
+fn bughouse() {
+    let mut game = Bughouse::default();
+    for m in ["e2e4", "d7d5", "e4d5"] {
+        game.make_move(0, m.parse().unwrap()).unwrap();
+    }
+    // White's capture on the first board goes to black, its partner, on the second
+    assert!(game.boards[0].pocket(Color::White).is_empty());
+    assert_eq!(game.boards[1].pocket(Color::Black).count(Piece::Pawn), 1);
+
+    game.make_move(1, "g1f3".parse().unwrap()).unwrap();
+    game.make_drop(1, "P@e5".parse().unwrap()).unwrap();
+    assert_eq!(
+        game.boards[1].to_fen(),
+        "rnbqkbnr/pppppppp/8/4p3/8/5N2/PPPPPPPP/RNBQKB1R[] w KQkq - 0 1"
+    );
+}
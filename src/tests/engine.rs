@@ -0,0 +1,193 @@
+use crate::engine::*;
+use crate::*;
+
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+#[test]
+fn mate_in_one() {
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+    let result = search(&board, Limits::depth(3)).unwrap();
+    assert_eq!(result.best_move, Some("a1a8".parse().unwrap()));
+    assert_eq!(result.score, Score::Mate(1));
+    assert_eq!(result.pv, ["a1a8".parse().unwrap()]);
+}
+
+#[test]
+fn mate_in_two() {
+    let board = Board::from_fen("k7/8/2K5/8/8/8/8/1R6 w - - 0 1").unwrap();
+    let result = search(&board, Limits::depth(4)).unwrap();
+    assert_eq!(result.score, Score::Mate(2));
+    assert_eq!(result.pv.len(), 3);
+}
+
+#[test]
+fn getting_mated() {
+    let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 0 1").unwrap();
+    let result = search(&board, Limits::depth(3)).unwrap();
+    assert_eq!(result.best_move, Some("h8g8".parse().unwrap()));
+    assert_eq!(result.score, Score::Mate(-1));
+}
+
+#[test]
+fn wins_material() {
+    let board = Board::from_fen("4k3/4p3/8/3q4/8/8/3R4/3RK3 w - - 0 1").unwrap();
+    let result = search(&board, Limits::depth(2)).unwrap();
+    assert_eq!(result.best_move, Some("d2d5".parse().unwrap()));
+    assert!(matches!(result.score, Score::Centipawns(score) if score > 400));
+}
+
+#[test]
+fn quiescence_sees_recapture() {
+    // Taking the defended pawn with the queen loses her
+    let board = Board::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+    let result = search(&board, Limits::depth(1)).unwrap();
+    assert_ne!(result.best_move, Some("d1d5".parse().unwrap()));
+}
+
+#[test]
+fn no_legal_moves() {
+    let board = Board::from_fen("k7/8/1Q6/8/8/8/8/4K3 b - - 0 1").unwrap();
+    let result = search(&board, Limits::depth(3)).unwrap();
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, Score::Centipawns(0));
+}
+
+#[test]
+fn node_limit() {
+    let result = search(&Board::default(), Limits::nodes(500)).unwrap();
+    assert!(result.best_move.is_some());
+    assert!(result.nodes <= 501);
+}
+
+#[test]
+fn time_limit() {
+    let result = search(&Board::default(), Limits::time(Duration::from_millis(50))).unwrap();
+    assert!(result.best_move.is_some());
+    assert!(result.depth >= 1);
+}
+
+#[test]
+fn table_store_and_probe() {
+    let mut table = TranspositionTable::new(1);
+    let m = "e2e4".parse().unwrap();
+    assert!(table.capacity() > 1000);
+    assert_eq!(table.probe(42), None);
+    table.store(42, 3, Bound::Exact, 25, Some(m));
+    let entry = table.probe(42).unwrap();
+    assert_eq!(
+        (entry.depth, entry.bound, entry.score, entry.best_move),
+        (3, Bound::Exact, 25, Some(m))
+    );
+    table.clear();
+    assert_eq!(table.probe(42), None);
+}
+
+#[test]
+fn table_replacement() {
+    // With a single slot every key collides
+    let mut table = TranspositionTable::new(0);
+    assert_eq!(table.capacity(), 1);
+    let m = "e2e4".parse().unwrap();
+    table.store(1, 5, Bound::Exact, 10, Some(m));
+    table.store(2, 2, Bound::Lower, 20, None);
+    assert_eq!(table.probe(1).unwrap().depth, 5);
+    assert_eq!(table.probe(2), None);
+
+    // A shallower result for the same position keeps the move it knew
+    table.store(1, 1, Bound::Upper, -5, None);
+    assert_eq!(table.probe(1).unwrap().best_move, Some(m));
+
+    // Entries from earlier searches give way
+    table.new_search();
+    table.store(2, 1, Bound::Lower, 20, None);
+    assert_eq!(table.probe(1), None);
+    assert_eq!(table.probe(2).unwrap().score, 20);
+}
+
+#[test]
+fn engine_reuses_table() {
+    let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+        .unwrap();
+    let mut engine = Engine::new(4);
+    let first = engine.search(&board, Limits::depth(2)).unwrap();
+    let second = engine.search(&board, Limits::depth(2)).unwrap();
+    assert!(second.nodes < first.nodes);
+    assert_eq!(second.best_move, first.best_move);
+}
+
+#[test]
+fn static_exchange_values() {
+    let see =
+        |fen: &str, m: &str| static_exchange(&Board::from_fen(fen).unwrap(), &m.parse().unwrap());
+    // Pawn for pawn
+    assert_eq!(see("4k3/8/2p5/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5"), 0);
+    // The queen wins a pawn and is lost to the recapture
+    assert_eq!(see("4k3/8/2p5/3p4/8/8/3Q4/4K3 w - - 0 1", "d2d5"), -800);
+    // Undefended
+    assert_eq!(see("4k3/8/8/3n4/8/8/3R4/4K3 w - - 0 1", "d2d5"), 320);
+    // The rook behind backs up the capture, so black is better off not recapturing
+    assert_eq!(see("3r1k2/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"), 100);
+    // En passant
+    assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+}
+
+#[test]
+fn quiescence_stays_small() {
+    // Without ordering and pruning losing captures this exchange-heavy position blows up
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    assert!(search(&board, Limits::depth(1)).unwrap().nodes < 10_000);
+}
+
+#[test]
+fn multipv_lines() {
+    // Only the rook mates, but the other lines still come back best first
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+    let lines = analyze(&board, 2, 3).unwrap();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].m, "a1a8".parse().unwrap());
+    assert_eq!(lines[0].score, Score::Mate(1));
+    assert_eq!(lines[0].san, ["Ra8#"]);
+    assert!(lines[1..]
+        .iter()
+        .all(|line| matches!(line.score, Score::Centipawns(_)) && line.depth == 2));
+    assert_ne!(lines[1].m, lines[2].m);
+
+    let mated = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(analyze(&mated, 2, 3).unwrap(), []);
+    let one_move = Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 0 1").unwrap();
+    assert_eq!(analyze(&one_move, 1, 3).unwrap().len(), 1);
+}
+
+#[test]
+fn analysis_streams_and_stops() {
+    let board = Board::default();
+    let mut depths = vec![];
+    let lines = Engine::default()
+        .analyze_with(&board, 2, 2, &AtomicBool::new(false), |lines| {
+            depths.push(lines[0].depth)
+        })
+        .unwrap();
+    assert_eq!(depths, [1, 2]);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].san.len(), lines[0].pv.len());
+
+    let stopped = Engine::default()
+        .analyze_with(&board, 5, 2, &AtomicBool::new(true), |_| {
+            panic!("nothing finished")
+        })
+        .unwrap();
+    assert_eq!(stopped, []);
+}
+
+#[test]
+fn drop_variants_are_refused() {
+    // The search would never see the knight blocking on b1, c1 or d1
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1").unwrap();
+    assert_eq!(board.variant(), Variant::Crazyhouse);
+    let refused = Error::Engine(EngineError::UnsupportedVariant(Variant::Crazyhouse));
+    assert_eq!(search(&board, Limits::depth(2)).unwrap_err(), refused);
+    assert_eq!(analyze(&board, 2, 3).unwrap_err(), refused);
+}
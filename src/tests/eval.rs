@@ -0,0 +1,112 @@
+use crate::eval::*;
+use crate::*;
+
+#[test]
+fn start_position_is_even() {
+    let board = Board::default();
+    let breakdown = breakdown(&board, &EvalParams::default());
+    assert_eq!(breakdown.material.white, 4000);
+    assert_eq!(breakdown.material.net(), 0);
+    assert_eq!(breakdown.total(), 0);
+    assert_eq!(evaluate(&board, &EvalParams::default()), 0);
+}
+
+#[test]
+fn mirrored_positions_mirror_scores() {
+    let white = Board::from_fen("4k3/pp6/8/3N4/8/8/1P3PPP/4K2R w - - 0 1").unwrap();
+    let black = Board::from_fen("4k2r/1p3ppp/8/8/3n4/8/PP6/4K3 b - - 0 1").unwrap();
+    let params = EvalParams::default();
+    assert_eq!(
+        breakdown(&white, &params).total(),
+        -breakdown(&black, &params).total()
+    );
+    assert_eq!(evaluate(&white, &params), evaluate(&black, &params));
+}
+
+#[test]
+fn side_to_move_perspective() {
+    let white = Board::from_fen("4k3/8/8/8/8/8/8/Q3K3 w - - 0 1").unwrap();
+    let black = Board::from_fen("4k3/8/8/8/8/8/8/Q3K3 b - - 0 1").unwrap();
+    let params = EvalParams::default();
+    assert!(evaluate(&white, &params) > 800);
+    assert_eq!(evaluate(&black, &params), -evaluate(&white, &params));
+}
+
+#[test]
+fn pawn_structure() {
+    let params = EvalParams {
+        passed_pawn: [0; 8],
+        ..Default::default()
+    };
+    // Doubled and isolated on the a-file, against a healthy pair
+    let board = Board::from_fen("4k3/5pp1/8/8/P7/P7/8/4K3 w - - 0 1").unwrap();
+    let term = breakdown(&board, &params).pawn_structure;
+    assert_eq!(term.white, 2 * (params.doubled_pawn + params.isolated_pawn));
+    assert_eq!(term.black, 0);
+}
+
+#[test]
+fn passed_pawns() {
+    let params = EvalParams::default();
+    // No pawn has an enemy pawn in front of it on its own or a neighbouring file
+    let board = Board::from_fen("4k3/p7/8/4P3/8/8/1p6/4K3 w - - 0 1").unwrap();
+    let term = breakdown(&board, &params).pawn_structure;
+    assert_eq!(term.white, params.passed_pawn[4] + params.isolated_pawn);
+    assert_eq!(term.black, params.passed_pawn[1] + params.passed_pawn[6]);
+}
+
+#[test]
+fn king_safety() {
+    let params = EvalParams::default();
+    let sheltered = Board::from_fen("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+    assert_eq!(
+        breakdown(&sheltered, &params).king_safety.white,
+        3 * params.pawn_shield
+    );
+
+    // The rook on the g-file hits g1 and g2 next to the bare king
+    let exposed = Board::from_fen("6k1/8/8/8/8/8/8/7K w - - 0 1").unwrap();
+    let attacked = Board::from_fen("6k1/6r1/8/8/8/8/8/7K w - - 0 1").unwrap();
+    assert_eq!(breakdown(&exposed, &params).king_safety.white, 0);
+    assert_eq!(
+        breakdown(&attacked, &params).king_safety.white,
+        2 * params.king_attack
+    );
+}
+
+#[test]
+fn terms_respond_to_params() {
+    let board = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1").unwrap();
+    let params = EvalParams {
+        mobility: [0, 10, 0, 0, 0, 0],
+        ..Default::default()
+    };
+    assert_eq!(breakdown(&board, &params).mobility.white, 80);
+    assert_eq!(breakdown(&board, &EvalParams::default()).mobility.white, 32);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn params_from_json() {
+    let params = EvalParams::from_json(r#"{ "doubled_pawn": -40 }"#).unwrap();
+    assert_eq!(params.doubled_pawn, -40);
+    assert_eq!(params.material, EvalParams::default().material);
+    assert_eq!(
+        EvalParams::from_json("{ \"material\": 3 }"),
+        Err(Error::Params(ParamsError::Parse))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn params_from_file() {
+    let path = std::env::temp_dir().join(format!("rust-chess-eval-{}.json", std::process::id()));
+    let params = EvalParams {
+        king_attack: -20,
+        ..Default::default()
+    };
+    std::fs::write(&path, serde_json::to_string(&params).unwrap()).unwrap();
+    assert_eq!(EvalParams::load(&path), Ok(params));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(EvalParams::load(&path), Err(Error::Params(ParamsError::Io)));
+}
@@ -0,0 +1,96 @@
+use crate::*;
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[test]
+fn default_board() {
+    assert_eq!(Board::default().to_fen(), START);
+}
+
+#[test]
+fn round_trip() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    assert_eq!(
+        Board::from_fen(START).unwrap().squares,
+        Board::default().squares
+    );
+}
+
+#[test]
+fn check_is_detected() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.state, BoardState::Check(Color::White));
+    let board = Board::from_fen("4k3/8/8/8/8/8/3qq3/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.state, BoardState::Checkmate(Color::White));
+}
+
+#[test]
+fn stalemate_keeps_side_to_move() {
+    let fen = "k7/8/1Q6/8/8/8/8/7K b - - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    assert_eq!(board.state, BoardState::Stalemate);
+    assert_eq!(board.to_fen(), fen);
+
+    // Also when the stalemating move was played on the board
+    let mut board = Board::from_fen("k7/8/8/1Q6/8/8/8/7K w - - 0 1").unwrap();
+    board.make_move("b5b6".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::Stalemate);
+    assert_eq!(board.to_fen(), fen);
+}
+
+#[test]
+fn invalid() {
+    for fen in [
+        "",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w - - 0 1",
+        "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1BNR w - - 0 1",
+        "3Pk3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/3pK3 w - - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBKKBNR w - - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w - - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x - - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w X - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - z9 0 1",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap_err(), Error::FenParse, "{fen}");
+    }
+}
+
+#[test]
+fn missing_king() {
+    // Horde plays without a white king, but castling still needs one
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1BNR w kq - 0 1";
+    assert_eq!(Board::from_fen(fen).unwrap_err(), Error::FenParse);
+    let board = Board::from_variant_fen(fen, Variant::Horde).unwrap();
+    assert_eq!(board.variant(), Variant::Horde);
+    assert_eq!(board.king(Color::White), None);
+    assert_eq!(board.king(Color::Black), Some("e8".parse().unwrap()));
+    assert_eq!(board.validate(), Ok(()));
+    assert_eq!(
+        Board::from_variant_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1BNR w KQkq - 0 1",
+            Variant::Horde
+        )
+        .unwrap_err(),
+        Error::FenParse
+    );
+}
+
+#[test]
+fn legal_moves() {
+    let board = Board::default();
+    let moves = board.legal_moves();
+    assert_eq!(moves.len(), 20);
+    assert!(moves.contains(&"e2e4".parse().unwrap()));
+    assert!(moves.contains(&"g1f3".parse().unwrap()));
+}
+
+#[test]
+fn legal_moves_in_check() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/4r3/R3K3 w - - 0 1").unwrap();
+    let mut moves: Vec<String> = board.legal_moves().iter().map(|m| m.to_string()).collect();
+    moves.sort();
+    assert_eq!(moves, ["e1d1", "e1e2", "e1f1"]);
+}
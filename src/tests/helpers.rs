@@ -0,0 +1,25 @@
+use crate::*;
+
+/// Counts the positions `depth` plies from `board`, for comparing with known perft results.
+pub fn perft(board: &Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = board.legal_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .iter()
+        .map(|m| {
+            let mut board = board.clone();
+            board.make_move(*m).unwrap();
+            perft(&board, depth - 1)
+        })
+        .sum()
+}
+
+/// Loads a FEN by `variant`'s rules.
+pub fn from_fen_as(fen: &str, variant: Variant) -> Board {
+    Board::from_variant_fen(fen, variant).unwrap()
+}
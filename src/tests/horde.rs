@@ -0,0 +1,71 @@
+use crate::tests::helpers::{from_fen_as, perft};
+use crate::*;
+
+#[test]
+fn horde_perft() {
+    let board = Variant::Horde.starting_position();
+    assert_eq!(board.king(Color::White), None);
+    assert_eq!(
+        board
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+            .filter(|troop| troop.color == Color::White && troop.piece == Piece::Pawn)
+            .count(),
+        36
+    );
+    assert_eq!(board.validate(), Ok(()));
+    assert_eq!(perft(&board, 1), 8);
+    assert_eq!(perft(&board, 2), 128);
+    assert_eq!(perft(&board, 3), 1274);
+}
+
+#[test]
+fn first_rank_double_push() {
+    let mut board = from_fen_as("4k3/8/8/8/8/8/8/P7 w - - 0 1", Variant::Horde);
+    let moves: Vec<String> = board.legal_moves().iter().map(|m| m.to_string()).collect();
+    assert_eq!(moves, ["a1a2", "a1a3"]);
+    board.make_move("a1a3".parse().unwrap()).unwrap();
+    assert_eq!(board.en_passant(), None);
+
+    // Other variants have no pawns on the first rank at all
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/P3K3 w - - 0 1").unwrap_err(),
+        Error::FenParse
+    );
+}
+
+#[test]
+fn horde_endings() {
+    // Black wins by taking the last pawn
+    let mut board = from_fen_as("4k3/8/8/8/8/8/1q6/P7 b - - 0 1", Variant::Horde);
+    board.make_move("b2a1".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantLoss(Color::White));
+    assert!(board.legal_moves().is_empty());
+
+    // White wins by checkmate as usual
+    let mut board = from_fen_as("k7/8/8/8/8/8/1R6/7R w - - 0 1", Variant::Horde);
+    board.make_move("h1a1".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::Checkmate(Color::Black));
+}
+
+#[test]
+fn kingless_setup() {
+    let board = BoardBuilder::new()
+        .variant(Variant::Horde)
+        .place(Piece::King, Color::Black, "e8".parse().unwrap())
+        .place(Piece::Pawn, Color::White, "a1".parse().unwrap())
+        .build()
+        .unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/P7 w - - 0 1");
+
+    assert_eq!(
+        BoardBuilder::new()
+            .variant(Variant::Horde)
+            .place(Piece::Pawn, Color::White, "a2".parse().unwrap())
+            .build()
+            .unwrap_err(),
+        Error::Setup(SetupError::KingCount(Color::Black))
+    );
+}
@@ -0,0 +1,126 @@
+use crate::tests::helpers::perft;
+use crate::*;
+
+fn pos(s: &str) -> Position {
+    s.parse().unwrap()
+}
+
+fn moves(board: &Board) -> Vec<String> {
+    board.legal_moves().iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn pins() {
+    let board = Board::from_fen("4k3/4r3/8/1b6/8/3N4/4B3/4K3 w - - 0 1").unwrap();
+    let mut pins = board.pins(Color::White);
+    pins.sort_by_key(|pin| pin.pinned);
+    assert_eq!(
+        pins,
+        [Pin {
+            pinned: pos("e2"),
+            pinner: pos("e7"),
+        }]
+    );
+    // The bishop on e2 may only move along the e-file, which it can't
+    assert!(!moves(&board).iter().any(|m| m.starts_with("e2")));
+    // The knight isn't pinned: the bishop on b5 doesn't line up with the king
+    assert!(moves(&board).iter().any(|m| m.starts_with("d3")));
+}
+
+#[test]
+fn pinned_slider_moves_along_pin() {
+    let board = Board::from_fen("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1").unwrap();
+    let mut rook_moves: Vec<String> = moves(&board)
+        .into_iter()
+        .filter(|m| m.starts_with("e2"))
+        .collect();
+    rook_moves.sort();
+    assert_eq!(rook_moves, ["e2e3", "e2e4", "e2e5", "e2e6", "e2e7"]);
+}
+
+#[test]
+fn checkers() {
+    let board = Board::from_fen("4k3/8/8/8/1b6/8/3N4/r3K3 w - - 0 1").unwrap();
+    assert_eq!(board.state, BoardState::Check(Color::White));
+    let mut checkers: Vec<Position> = board
+        .checkers(Color::White)
+        .iter()
+        .map(|troop| troop.position)
+        .collect();
+    checkers.sort();
+    assert_eq!(checkers, [pos("a1")]);
+    // The knight could block on b1, but it's pinned by the bishop
+    let mut evasions = moves(&board);
+    evasions.sort();
+    assert_eq!(evasions, ["e1e2", "e1f2"]);
+}
+
+#[test]
+fn double_check() {
+    let board = Board::from_fen("4k3/8/8/8/7b/8/4N3/r3K2R w K - 0 1").unwrap();
+    assert_eq!(board.checkers(Color::White).len(), 2);
+    let mut evasions = moves(&board);
+    evasions.sort();
+    assert_eq!(evasions, ["e1d2"]);
+}
+
+#[test]
+fn en_passant_discovered_check() {
+    // Taking en passant would clear the fifth rank between the rook and the king
+    let board = Board::from_fen("8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1").unwrap();
+    assert!(!moves(&board).contains(&"e5d6".to_string()));
+}
+
+#[test]
+fn checkmate() {
+    let mut board = Board::default();
+    for (from, to) in [("f2", "f3"), ("e7", "e6"), ("g2", "g4"), ("d8", "h4")] {
+        board.move_troop(pos(from), pos(to)).unwrap();
+    }
+    assert_eq!(board.state, BoardState::Checkmate(Color::White));
+    assert_eq!(
+        board.move_troop(pos("a2"), pos("a3")),
+        Err(Error::Move(MoveError::GameOver))
+    );
+}
+
+#[test]
+fn stalemate() {
+    let mut board = Board::from_fen("k7/8/2Q5/8/8/8/8/4K3 w - - 0 1").unwrap();
+    board.move_troop(pos("c6"), pos("b6")).unwrap();
+    assert_eq!(board.state, BoardState::Stalemate);
+}
+
+#[test]
+fn perft_start() {
+    let board = Board::default();
+    assert_eq!(perft(&board, 1), 20);
+    assert_eq!(perft(&board, 2), 400);
+    assert_eq!(perft(&board, 3), 8902);
+}
+
+#[test]
+fn perft_kiwipete() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    assert_eq!(perft(&board, 1), 48);
+    assert_eq!(perft(&board, 2), 2039);
+}
+
+#[test]
+fn perft_position_3() {
+    let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+    assert_eq!(perft(&board, 1), 14);
+    assert_eq!(perft(&board, 2), 191);
+    assert_eq!(perft(&board, 3), 2812);
+}
+
+#[test]
+fn perft_position_4() {
+    let board = Board::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1")
+        .unwrap();
+    assert_eq!(perft(&board, 1), 6);
+    assert_eq!(perft(&board, 2), 264);
+    assert_eq!(perft(&board, 3), 9467);
+}
@@ -78,3 +78,55 @@ fn move_into_check() {
         Err(Error::Move(MoveError::Other)),
     );
 }
+
+#[test]
+fn castling() {
+    let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    board
+        .move_troop("e1".parse().unwrap(), "g1".parse().unwrap())
+        .unwrap();
+    board
+        .move_troop("e8".parse().unwrap(), "c8".parse().unwrap())
+        .unwrap();
+    assert_eq!(board.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 0 1");
+}
+
+#[test]
+fn castling_through_check() {
+    let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    board
+        .place_troop(Troop {
+            piece: Piece::Rook,
+            color: Color::Black,
+            position: Position {
+                file: File::F,
+                rank: Rank::Five,
+            },
+        })
+        .unwrap();
+    assert_eq!(
+        board.move_troop("e1".parse().unwrap(), "g1".parse().unwrap()),
+        Err(Error::Move(MoveError::Other)),
+    );
+    assert_eq!(
+        board.move_troop("e1".parse().unwrap(), "c1".parse().unwrap()),
+        Ok(()),
+    );
+}
+
+#[test]
+fn castling_rights_lost() {
+    let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    board
+        .move_troop("h1".parse().unwrap(), "h8".parse().unwrap())
+        .unwrap();
+    assert_eq!(
+        board.castling_rights(),
+        CastlingRights {
+            white_kingside: false,
+            white_queenside: true,
+            black_kingside: false,
+            black_queenside: true,
+        }
+    );
+}
@@ -166,6 +166,35 @@ fn blocked_path() {
     );
 }
 
+#[test]
+fn blocked_by_enemy() {
+    let mut board = Board::default();
+    board
+        .place_troop(Troop {
+            piece: Piece::Pawn,
+            color: Color::Black,
+            position: Position {
+                file: File::A,
+                rank: Rank::Three,
+            },
+        })
+        .unwrap();
+
+    assert_eq!(
+        board.move_troop(
+            Position {
+                file: File::A,
+                rank: Rank::Two,
+            },
+            Position {
+                file: File::A,
+                rank: Rank::Three,
+            }
+        ),
+        Err(Error::Move(MoveError::Other))
+    );
+}
+
 #[test]
 fn backwards() {
     let mut board = Board::default();
@@ -198,3 +227,77 @@ fn backwards() {
         Err(Error::Move(MoveError::Other))
     );
 }
+
+#[test]
+fn en_passant() {
+    let mut board = Board::default();
+    for (from, to) in [("e2", "e4"), ("a7", "a6"), ("e4", "e5"), ("d7", "d5")] {
+        board
+            .move_troop(from.parse().unwrap(), to.parse().unwrap())
+            .unwrap();
+    }
+    assert_eq!(board.en_passant(), Some("d6".parse().unwrap()));
+    board
+        .move_troop("e5".parse().unwrap(), "d6".parse().unwrap())
+        .unwrap();
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/1pp1pppp/p2P4/8/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    );
+}
+
+#[test]
+fn en_passant_expires() {
+    let mut board = Board::default();
+    for (from, to) in [
+        ("e2", "e4"),
+        ("a7", "a6"),
+        ("e4", "e5"),
+        ("d7", "d5"),
+        ("h2", "h3"),
+        ("h7", "h6"),
+    ] {
+        board
+            .move_troop(from.parse().unwrap(), to.parse().unwrap())
+            .unwrap();
+    }
+    assert_eq!(
+        board.move_troop("e5".parse().unwrap(), "d6".parse().unwrap()),
+        Err(Error::Move(MoveError::Other))
+    );
+}
+
+#[test]
+fn promotion() {
+    let mut board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.make_move(Move {
+            from: "b7".parse().unwrap(),
+            to: "b8".parse().unwrap(),
+            promotion: Some(Piece::King),
+        }),
+        Err(Error::Move(MoveError::Other))
+    );
+    board.make_move("b7b8n".parse().unwrap()).unwrap();
+    assert_eq!(board.to_fen(), "1N2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    assert_eq!(board.last_move(), Some("b7b8n".parse().unwrap()));
+}
+
+#[test]
+fn promotion_defaults_to_queen() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/6p1/4K3 b - - 0 1").unwrap();
+    board
+        .move_troop(
+            Position {
+                file: File::G,
+                rank: Rank::Two,
+            },
+            Position {
+                file: File::G,
+                rank: Rank::One,
+            },
+        )
+        .unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K1q1 w - - 0 1");
+    assert_eq!(board.state, BoardState::Check(Color::White));
+}
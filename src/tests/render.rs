@@ -0,0 +1,77 @@
+use crate::render::*;
+use crate::*;
+
+fn ascii() -> Renderer {
+    Renderer {
+        glyphs: Glyphs::Ascii,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn white_at_the_bottom() {
+    let text = Board::default().to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(lines[0], "8  ♜  ♞  ♝  ♛  ♚  ♝  ♞  ♜ ");
+    assert_eq!(lines[7], "1  ♖  ♘  ♗  ♕  ♔  ♗  ♘  ♖ ");
+    assert_eq!(lines[8], "   a  b  c  d  e  f  g  h");
+}
+
+#[test]
+fn black_perspective() {
+    let renderer = Renderer {
+        perspective: Color::Black,
+        ..ascii()
+    };
+    let text = renderer.render(&Board::default());
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "1  R  N  B  K  Q  B  N  R ");
+    assert_eq!(lines[7], "8  r  n  b  k  q  b  n  r ");
+    assert_eq!(lines[8], "   h  g  f  e  d  c  b  a");
+}
+
+#[test]
+fn without_coordinates() {
+    let renderer = Renderer {
+        coordinates: false,
+        ..ascii()
+    };
+    let text = renderer.render(&Board::default());
+    assert_eq!(text.lines().count(), 8);
+    assert_eq!(text.lines().next().unwrap(), " r  n  b  q  k  b  n  r ");
+}
+
+#[test]
+fn highlights() {
+    let mut board = Board::default();
+    for m in ["e2e4", "f7f6", "d1h5"] {
+        board.make_move(m.parse().unwrap()).unwrap();
+    }
+    let text = ascii().render(&board);
+    let lines: Vec<&str> = text.lines().collect();
+    // Black's king is in check from the queen that just moved
+    assert_eq!(lines[0], "8  r  n  b  q (k) b  n  r ");
+    assert_eq!(lines[3], "5  .  .  .  .  .  .  . [Q]");
+    assert_eq!(lines[7], "1  R  N  B [.] K  B  N  R ");
+
+    let plain = Renderer {
+        highlight_last_move: false,
+        highlight_check: false,
+        ..ascii()
+    };
+    assert!(!plain.render(&board).contains(['[', '(']));
+}
+
+#[test]
+fn ansi_colours() {
+    let renderer = Renderer {
+        ansi: true,
+        ..Default::default()
+    };
+    let text = renderer.render(&Board::default());
+    let first = text.lines().next().unwrap();
+    assert!(first.starts_with("8 \x1b[48;5;180m"));
+    assert!(first.ends_with("\x1b[0m"));
+    assert!(first.contains('♜'));
+}
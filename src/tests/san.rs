@@ -0,0 +1,73 @@
+use crate::*;
+
+fn san(fen: &str, m: &str) -> String {
+    Board::from_fen(fen)
+        .unwrap()
+        .san(m.parse().unwrap())
+        .unwrap()
+}
+
+#[test]
+fn pieces_and_pawns() {
+    let start = Board::default().to_fen();
+    assert_eq!(san(&start, "e2e4"), "e4");
+    assert_eq!(san(&start, "g1f3"), "Nf3");
+    let fen = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
+    assert_eq!(san(fen, "e4d5"), "exd5");
+    let en_passant = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2";
+    assert_eq!(san(en_passant, "e5d6"), "exd6");
+}
+
+#[test]
+fn disambiguation() {
+    // Knights on b1 and f3 can both reach d2, rooks on a1 and a5 both reach a3
+    let fen = "4k3/8/8/R7/8/8/8/RN2KN2 w - - 0 1";
+    assert_eq!(san(fen, "b1d2"), "Nbd2");
+    assert_eq!(san(fen, "a1a3"), "R1a3");
+    assert_eq!(san(fen, "a5a3"), "R5a3");
+    // Three queens that need both file and rank
+    let queens = "4k3/8/8/8/Q1Q5/8/Q7/4K3 w - - 0 1";
+    assert_eq!(san(queens, "a4b3"), "Qa4b3");
+}
+
+#[test]
+fn castling_promotion_and_check() {
+    let castling = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san(castling, "e1g1"), "O-O");
+    assert_eq!(san(castling, "e1c1"), "O-O-O");
+    let promotion = "7k/1P6/8/8/8/8/8/4K3 w - - 0 1";
+    assert_eq!(san(promotion, "b7b8q"), "b8=Q+");
+    assert_eq!(san(promotion, "b7b8n"), "b8=N");
+    assert_eq!(san(promotion, "b7b8"), "b8=Q+");
+    assert_eq!(san("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", "a1a8"), "Ra8#");
+}
+
+#[test]
+fn lines_and_illegal_moves() {
+    let moves: Vec<Move> = ["e2e4", "e7e5", "g1f3"]
+        .iter()
+        .map(|m| m.parse().unwrap())
+        .collect();
+    assert_eq!(
+        Board::default().san_line(&moves).unwrap(),
+        ["e4", "e5", "Nf3"]
+    );
+    assert!(Board::default().san("e2e5".parse().unwrap()).is_err());
+}
+
+#[test]
+fn parse() {
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/1P6/RN2K2R w KQkq - 0 1").unwrap();
+    let parse = |san: &str| board.parse_san(san);
+    assert_eq!(parse("b4"), Ok("b2b4".parse().unwrap()));
+    assert_eq!(parse("Nc3"), Ok("b1c3".parse().unwrap()));
+    assert_eq!(parse("O-O"), Ok("e1g1".parse().unwrap()));
+    assert_eq!(parse("0-0-0"), Err(Error::SanParse));
+    assert_eq!(parse("Rxh8+!"), Ok("h1h8".parse().unwrap()));
+    assert_eq!(parse("Ke3"), Err(Error::SanParse));
+    assert_eq!(parse("hello"), Err(Error::SanParse));
+    let castling = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert_eq!(castling.parse_san("0-0-0"), Ok("e1c1".parse().unwrap()));
+    let promotion = Board::from_fen("7k/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(promotion.parse_san("b8N"), Ok("b7b8n".parse().unwrap()));
+}
@@ -0,0 +1,67 @@
+use crate::store::*;
+use crate::*;
+
+fn exercise(store: &mut impl GameStore) {
+    let mut board = Board::default();
+    let id = store.create(&board).unwrap();
+    assert_eq!(store.list(), Ok(vec![id]));
+
+    board
+        .move_troop(
+            Position {
+                file: File::E,
+                rank: Rank::Two,
+            },
+            Position {
+                file: File::E,
+                rank: Rank::Four,
+            },
+        )
+        .unwrap();
+    store.save(id, &board).unwrap();
+
+    let loaded = store.load(id).unwrap();
+    assert_eq!(loaded.squares, board.squares);
+    assert_eq!(loaded.state, BoardState::ToMove(Color::Black));
+
+    let second = store.create(&Board::default()).unwrap();
+    assert_ne!(id, second);
+    assert_eq!(store.list(), Ok(vec![id, second]));
+
+    store.delete(id).unwrap();
+    assert_eq!(store.list(), Ok(vec![second]));
+    assert_eq!(
+        store.load(id).unwrap_err(),
+        Error::Store(StoreError::NotFound)
+    );
+    assert_eq!(
+        store.save(id, &board),
+        Err(Error::Store(StoreError::NotFound))
+    );
+    assert_eq!(store.delete(id), Err(Error::Store(StoreError::NotFound)));
+
+    // Deleting the newest game doesn't free its id
+    store.delete(second).unwrap();
+    let third = store.create(&Board::default()).unwrap();
+    assert!(third != id && third != second);
+    assert_eq!(store.list(), Ok(vec![third]));
+}
+
+#[test]
+fn memory_store() {
+    exercise(&mut MemoryStore::new());
+}
+
+#[test]
+fn file_store() {
+    let directory = std::env::temp_dir().join(format!("rust-chess-store-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    exercise(&mut FileStore::open(&directory).unwrap());
+
+    let mut reopened = FileStore::open(&directory).unwrap();
+    let ids = reopened.list().unwrap();
+    assert_eq!(ids.len(), 1);
+    reopened.delete(ids[0]).unwrap();
+    assert!(reopened.create(&Board::default()).unwrap() > ids[0]);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,68 @@
+use crate::svg::*;
+use crate::*;
+
+use std::path::PathBuf;
+
+/// Compares with the file in `src/tests/golden`, or rewrites it when `UPDATE_GOLDEN` is set.
+fn golden(name: &str, svg: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, svg).unwrap();
+    }
+    assert_eq!(svg, std::fs::read_to_string(&path).unwrap());
+}
+
+fn position(name: &str) -> Position {
+    name.parse().unwrap()
+}
+
+#[test]
+fn start_position() {
+    let svg = Diagram::default().render(&Board::default());
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"360\""));
+    // Coordinates are the only text, and every kind of piece is drawn once and placed with <use>
+    assert_eq!(svg.matches("<text").count(), 16);
+    assert_eq!(svg.matches("<symbol").count(), 12);
+    assert_eq!(svg.matches("<use").count(), 32);
+    golden("start.svg", &svg);
+}
+
+#[test]
+fn flipped_without_coordinates() {
+    let diagram = Diagram {
+        perspective: Color::Black,
+        coordinates: false,
+        ..Default::default()
+    };
+    let svg = diagram.render(&Board::default());
+    // a1 is in the top right corner
+    assert!(svg.contains("<rect x=\"280\" y=\"0\" width=\"40\" height=\"40\" fill=\"#b58863\"/>"));
+    assert!(!svg.contains("font-family=\"sans-serif\""));
+    golden("flipped.svg", &svg);
+}
+
+#[test]
+fn highlights_arrows_and_check() {
+    let mut board = Board::default();
+    for m in ["e2e4", "f7f6", "d1h5"] {
+        board.make_move(m.parse().unwrap()).unwrap();
+    }
+    let diagram = Diagram {
+        highlights: vec![position("d1"), position("h5")],
+        arrows: vec![(position("g7"), position("g6"))],
+        ..Default::default()
+    };
+    let svg = diagram.render(&board);
+    assert!(svg.contains("fill=\"url(#check)\""));
+    assert_eq!(svg.matches("opacity=\"0.8\"").count(), 3);
+    assert!(svg.contains("marker-end=\"url(#arrowhead)\""));
+    golden("check.svg", &svg);
+
+    let quiet = Diagram {
+        check: false,
+        ..Default::default()
+    };
+    assert!(!quiet.render(&board).contains("url(#check)\"/>"));
+}
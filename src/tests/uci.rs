@@ -0,0 +1,138 @@
+use crate::uci::run;
+use crate::*;
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Collects what a protocol writes, while the test keeps a handle to read it back.
+#[derive(Clone, Default)]
+pub struct SharedOutput(pub Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn script(input: &str) -> Vec<String> {
+    let output = SharedOutput::default();
+    run(input.as_bytes(), output.clone()).unwrap();
+    let bytes = output.0.lock().unwrap().clone();
+    String::from_utf8(bytes)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+fn best_move(lines: &[String]) -> Move {
+    let line = lines
+        .iter()
+        .find(|line| line.starts_with("bestmove"))
+        .unwrap();
+    line.split_whitespace().nth(1).unwrap().parse().unwrap()
+}
+
+#[test]
+fn handshake() {
+    let lines = script("uci\nisready\n");
+    assert!(lines[0].starts_with("id name rust-chess"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("option name Hash")));
+    assert_eq!(lines[lines.len() - 2], "uciok");
+    assert_eq!(lines[lines.len() - 1], "readyok");
+}
+
+#[test]
+fn position_with_moves() {
+    let lines = script("position startpos moves e2e4 e7e5 g1f3\ngo depth 1\n");
+    let mut board = Board::default();
+    for m in ["e2e4", "e7e5", "g1f3"] {
+        board.make_move(m.parse().unwrap()).unwrap();
+    }
+    assert!(board.legal_moves().contains(&best_move(&lines)));
+    assert!(lines[0].starts_with("info depth 1 score cp "));
+    assert!(lines[0].contains(" pv "));
+}
+
+#[test]
+fn mate_from_fen() {
+    let lines = script("position fen 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1\ngo depth 3\n");
+    assert_eq!(best_move(&lines), "a1a8".parse().unwrap());
+    assert!(lines.iter().any(|line| line.contains("score mate 1")));
+}
+
+#[test]
+fn no_legal_moves() {
+    let lines = script("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\ngo depth 2\n");
+    assert_eq!(lines.last().unwrap(), "bestmove 0000");
+}
+
+#[test]
+fn illegal_move() {
+    let lines = script("position startpos moves e2e5\ngo depth 1\n");
+    assert_eq!(lines[0], "info string illegal move e2e5");
+    assert!(Board::default().legal_moves().contains(&best_move(&lines)));
+
+    // Moves before the illegal one aren't played either, so the last position stands
+    let lines = script(
+        "position fen 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1\n\
+         position startpos moves e2e4 e2e5\ngo depth 3\n",
+    );
+    assert_eq!(lines[0], "info string illegal move e2e5");
+    assert_eq!(best_move(&lines), "a1a8".parse().unwrap());
+}
+
+#[test]
+fn infinite_until_stopped() {
+    let lines = script("ucinewgame\nposition startpos\ngo infinite\nisready\nstop\n");
+    assert!(lines.contains(&"readyok".to_string()));
+    assert!(Board::default().legal_moves().contains(&best_move(&lines)));
+}
+
+#[test]
+fn clock_and_movetime() {
+    for go in ["go movetime 20", "go wtime 200 btime 200 winc 0 binc 0"] {
+        let lines = script(&format!(
+            "setoption name Hash value 1\nposition startpos\n{}\n",
+            go
+        ));
+        assert!(Board::default().legal_moves().contains(&best_move(&lines)));
+    }
+}
+
+#[test]
+fn quit_stops_search() {
+    let lines = script("position startpos\ngo infinite\nquit\nisready\n");
+    assert!(!lines.contains(&"readyok".to_string()));
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.starts_with("bestmove"))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn chess960_castling() {
+    let lines = script(
+        "uci\nsetoption name UCI_Chess960 value true\n\
+         position startpos moves e2e4 e7e5 g1f3 g8f6 f1c4 f8c5 e1h1\ngo depth 1\n",
+    );
+    assert!(lines
+        .iter()
+        .any(|line| line == "option name UCI_Chess960 type check default false"));
+    assert!(!lines.iter().any(|line| line.starts_with("info string")));
+    let mut board = Board::default();
+    board.set_chess960(true);
+    for m in ["e2e4", "e7e5", "g1f3", "g8f6", "f1c4", "f8c5", "e1h1"] {
+        board.make_move(m.parse().unwrap()).unwrap();
+    }
+    assert!(board.legal_moves().contains(&best_move(&lines)));
+}
@@ -0,0 +1,137 @@
+use crate::*;
+
+const E1: Position = Position {
+    file: File::E,
+    rank: Rank::One,
+};
+const E2: Position = Position {
+    file: File::E,
+    rank: Rank::Two,
+};
+
+#[test]
+fn default_board_is_consistent() {
+    assert_eq!(Board::default().validate(), Ok(()));
+}
+
+#[test]
+fn reports_every_inconsistency() {
+    let mut board = Board::default();
+    board
+        .squares
+        .get_mut(&File::A)
+        .unwrap()
+        .remove(&Rank::Three);
+    board
+        .squares
+        .get_mut(&File::B)
+        .unwrap()
+        .get_mut(&Rank::Three)
+        .unwrap()
+        .position = E2;
+    board.get_mut_square(&E2).troop.as_mut().unwrap().position = E1;
+    board.white_king = Some(E2);
+    board
+        .place_troop(Troop {
+            piece: Piece::King,
+            color: Color::Black,
+            position: Position {
+                file: File::D,
+                rank: Rank::Five,
+            },
+        })
+        .unwrap();
+
+    assert_eq!(
+        board.validate(),
+        Err(vec![
+            Inconsistency::MissingSquare(Position {
+                file: File::A,
+                rank: Rank::Three,
+            }),
+            Inconsistency::SquarePosition {
+                key: Position {
+                    file: File::B,
+                    rank: Rank::Three,
+                },
+                position: E2,
+            },
+            Inconsistency::TroopPosition {
+                square: E2,
+                position: E1,
+            },
+            Inconsistency::KingPosition {
+                color: Color::White,
+                position: E2,
+            },
+            Inconsistency::KingCount {
+                color: Color::Black,
+                count: 2,
+            },
+        ])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn checked_deserialize() {
+    let mut board = Board::default();
+    let json = serde_json::to_string(&board).unwrap();
+    assert!(Board::from_json_checked(&json).is_ok());
+    assert_eq!(
+        Board::from_json_checked("{}").unwrap_err(),
+        Error::BoardParse
+    );
+
+    board.black_king = Some(E1);
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(
+        Board::from_json_checked(&json).unwrap_err(),
+        Error::InvalidBoard(vec![Inconsistency::KingPosition {
+            color: Color::Black,
+            position: E1,
+        }])
+    );
+    let err =
+        Board::deserialize_checked(&mut serde_json::Deserializer::from_str(&json)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Black king is tracked on e1, but isn't there"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn pawns_on_back_rank() {
+    let d8 = Position {
+        file: File::D,
+        rank: Rank::Eight,
+    };
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    board
+        .place_troop(Troop {
+            piece: Piece::Pawn,
+            color: Color::White,
+            position: d8,
+        })
+        .unwrap();
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(
+        Board::from_json_checked(&json).unwrap_err(),
+        Error::InvalidBoard(vec![Inconsistency::PawnOnBackRank(d8)])
+    );
+
+    // Horde's white pawns start on the first rank
+    let mut board =
+        Board::from_variant_fen("4k3/8/8/8/8/8/P7/8 w - - 0 1", Variant::Horde).unwrap();
+    board
+        .place_troop(Troop {
+            piece: Piece::Pawn,
+            color: Color::White,
+            position: E1,
+        })
+        .unwrap();
+    let json = serde_json::to_string(&board).unwrap();
+    let board = Board::from_json_checked(&json).unwrap();
+    assert_eq!(board.legal_moves().len(), 4);
+}
@@ -0,0 +1,82 @@
+use crate::engine::{search, Limits};
+use crate::tests::helpers::{from_fen_as, perft};
+use crate::*;
+
+#[test]
+fn king_of_the_hill() {
+    let mut board = from_fen_as("4k3/8/8/8/8/3K4/8/8 w - - 0 1", Variant::KingOfTheHill);
+    board.make_move("d3d4".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantLoss(Color::Black));
+    assert!(board.legal_moves().is_empty());
+    assert_eq!(
+        board.make_move("e8e7".parse().unwrap()),
+        Err(Error::Move(MoveError::GameOver))
+    );
+
+    let board = from_fen_as("4k3/8/8/8/8/3K4/8/8 w - - 0 1", Variant::KingOfTheHill);
+    let best = search(&board, Limits::depth(2)).unwrap().best_move.unwrap();
+    assert!(matches!(best.to.to_string().as_str(), "d4" | "e4"));
+
+    let board = Variant::KingOfTheHill.starting_position();
+    assert_eq!(board.to_fen(), Board::default().to_fen());
+    assert_eq!(board.variant(), Variant::KingOfTheHill);
+}
+
+#[test]
+fn three_check() {
+    let mut board = Variant::ThreeCheck.starting_position();
+    assert!(board.to_fen().ends_with(" +0+0"));
+    for m in ["e2e4", "e7e5", "f1b5", "a7a6", "b5d7"] {
+        board.make_move(m.parse().unwrap()).unwrap();
+    }
+    assert_eq!(board.checks_given(Color::White), 1);
+    assert!(board.to_fen().ends_with(" +1+0"));
+
+    let mut board = from_fen_as("4k3/8/8/8/8/8/8/4K2R w - - 0 1 +2+0", Variant::ThreeCheck);
+    assert_eq!(board.checks_given(Color::White), 2);
+    board.make_move("h1h8".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantLoss(Color::Black));
+
+    // Counts only matter for Three-check, but they still tell positions apart
+    let fresh = from_fen_as("4k3/8/8/8/8/8/8/4K2R w - - 0 1", Variant::ThreeCheck);
+    let checked = from_fen_as("4k3/8/8/8/8/8/8/4K2R w - - 0 1 +2+0", Variant::ThreeCheck);
+    assert_ne!(fresh.zobrist_hash(), checked.zobrist_hash());
+    assert!(Board::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 0 1 2+0").is_err());
+}
+
+#[test]
+fn racing_kings() {
+    let board = Variant::RacingKings.starting_position();
+    assert_eq!(perft(&board, 1), 21);
+    assert_eq!(perft(&board, 2), 421);
+    assert_eq!(perft(&board, 3), 11264);
+
+    // No move may give check, not even a capture
+    let board = from_fen_as("8/8/8/8/8/k7/8/1R5K w - - 0 1", Variant::RacingKings);
+    let moves = board.legal_moves();
+    assert!(!moves.contains(&"b1a1".parse().unwrap()));
+    assert!(!moves.contains(&"b1b3".parse().unwrap()));
+    assert!(moves.contains(&"b1b2".parse().unwrap()));
+
+    // Black gets one more move to catch up
+    let mut board = from_fen_as("8/1k4K1/8/8/8/8/8/8 w - - 0 1", Variant::RacingKings);
+    board.make_move("g7g8".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::ToMove(Color::Black));
+    board.make_move("b7b8".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::Draw);
+
+    let mut board = from_fen_as("8/6K1/1k6/8/8/8/8/8 w - - 0 1", Variant::RacingKings);
+    board.make_move("g7g8".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantLoss(Color::Black));
+
+    // Passing up the chance to catch up loses too
+    let mut board = from_fen_as("6K1/1k6/8/8/8/8/8/8 b - - 0 1", Variant::RacingKings);
+    assert_eq!(board.state, BoardState::ToMove(Color::Black));
+    board.make_move("b7a6".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantWin(Color::White));
+    assert_eq!(board.to_fen(), "6K1/8/k7/8/8/8/8/8 w - - 0 1");
+
+    let mut board = from_fen_as("8/1k6/8/8/8/8/6K1/8 b - - 0 1", Variant::RacingKings);
+    board.make_move("b7b8".parse().unwrap()).unwrap();
+    assert_eq!(board.state, BoardState::VariantLoss(Color::White));
+}
@@ -0,0 +1,105 @@
+use crate::tests::helpers::from_fen_as;
+use crate::wire::*;
+use crate::*;
+
+#[test]
+fn round_trip() {
+    let mut board = Board::default();
+    board
+        .move_troop(
+            Position {
+                file: File::E,
+                rank: Rank::Two,
+            },
+            Position {
+                file: File::E,
+                rank: Rank::Four,
+            },
+        )
+        .unwrap();
+
+    let json = to_json(&board);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["version"], SCHEMA_VERSION);
+    assert_eq!(value["variant"], "standard");
+    assert_eq!(
+        value["fen"],
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+    assert_eq!(
+        value["state"],
+        serde_json::json!({ "kind": "to_move", "color": "black" })
+    );
+    assert_eq!(value["legal_moves"].as_array().unwrap().len(), 20);
+
+    let loaded = from_json(&json).unwrap();
+    assert_eq!(loaded.squares, board.squares);
+    assert_eq!(loaded.state, board.state);
+}
+
+#[test]
+fn variants_round_trip() {
+    let boards = [
+        Board::default(),
+        from_fen_as("4k3/8/8/3K4/8/8/8/8 b - - 0 1", Variant::KingOfTheHill),
+        from_fen_as("4k3/8/8/8/8/8/8/4K2R w - - 0 1 +2+1", Variant::ThreeCheck),
+        Variant::RacingKings.starting_position(),
+        from_fen_as("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1", Variant::Crazyhouse),
+        from_fen_as("4k3/8/8/8/8/8/8/4K3[Qp] w - - 0 1", Variant::Bughouse),
+        Variant::Atomic.starting_position(),
+        from_fen_as("K3k3/8/8/8/8/8/8/4K3 b - - 0 1", Variant::Antichess),
+        Variant::Horde.starting_position(),
+    ];
+    for board in boards {
+        let dto = BoardDto::from(&board);
+        assert_eq!(Variant::from(dto.variant), board.variant());
+        let loaded = from_json(&to_json(&board)).unwrap();
+        assert_eq!(loaded.variant(), board.variant());
+        assert_eq!(loaded.to_fen(), board.to_fen());
+        assert_eq!(loaded.state, board.state);
+        for color in [Color::White, Color::Black] {
+            assert_eq!(loaded.checks_given(color), board.checks_given(color));
+        }
+    }
+}
+
+#[test]
+fn rejects_unknown_version() {
+    let mut dto = BoardDto::from(&Board::default());
+    dto.version = SCHEMA_VERSION + 1;
+    assert_eq!(
+        Board::try_from(dto).unwrap_err(),
+        Error::Wire(WireError::UnsupportedVersion)
+    );
+}
+
+#[test]
+fn rejects_inconsistent_state() {
+    let mut dto = BoardDto::from(&Board::default());
+    dto.state = StateDto::Check(ColorDto::White);
+    assert_eq!(
+        Board::try_from(dto).unwrap_err(),
+        Error::Wire(WireError::StateMismatch)
+    );
+}
+
+#[test]
+fn rejects_inconsistent_legal_moves() {
+    let mut dto = BoardDto::from(&Board::default());
+    dto.legal_moves.push("e2e5".to_string());
+    assert_eq!(
+        Board::try_from(dto).unwrap_err(),
+        Error::Wire(WireError::LegalMovesMismatch)
+    );
+}
+
+#[test]
+fn rejects_malformed_payloads() {
+    assert_eq!(
+        from_json("{\"version\": 2}").unwrap_err(),
+        Error::Wire(WireError::Json)
+    );
+    let mut dto = BoardDto::from(&Board::default());
+    dto.fen = "not a fen".to_string();
+    assert_eq!(Board::try_from(dto).unwrap_err(), Error::FenParse);
+}
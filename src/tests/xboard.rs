@@ -0,0 +1,119 @@
+use super::uci::SharedOutput;
+use crate::xboard::run;
+use crate::*;
+
+fn script(input: &str) -> Vec<String> {
+    let output = SharedOutput::default();
+    run(input.as_bytes(), output.clone()).unwrap();
+    let bytes = output.0.lock().unwrap().clone();
+    String::from_utf8(bytes)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+fn engine_moves(lines: &[String]) -> Vec<Move> {
+    lines
+        .iter()
+        .filter_map(|line| line.strip_prefix("move "))
+        .map(|m| m.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn features() {
+    let lines = script("xboard\nprotover 2\nping 7\n");
+    assert!(lines[0].starts_with("feature myname=\"rust-chess"));
+    assert!(lines[0].contains("usermove=1") && lines[0].contains("setboard=1"));
+    assert!(lines[0].ends_with("done=1"));
+    assert_eq!(lines[1], "pong 7");
+}
+
+#[test]
+fn answers_user_moves() {
+    let lines = script("new\nsd 2\nusermove e2e4\n");
+    let mut board = Board::default();
+    board.make_move("e2e4".parse().unwrap()).unwrap();
+    let moves = engine_moves(&lines);
+    assert_eq!(moves.len(), 1);
+    assert!(board.legal_moves().contains(&moves[0]));
+}
+
+#[test]
+fn force_mode() {
+    let lines = script("new\nforce\nusermove e2e4\nusermove e7e5\nping 1\n");
+    assert_eq!(lines, ["pong 1"]);
+}
+
+#[test]
+fn go_plays_side_to_move() {
+    let lines = script("new\nforce\nusermove e2e4\nsd 1\ngo\n");
+    let mut board = Board::default();
+    board.make_move("e2e4".parse().unwrap()).unwrap();
+    assert!(board.legal_moves().contains(&engine_moves(&lines)[0]));
+}
+
+#[test]
+fn setboard_and_result() {
+    let lines = script("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1\nsd 3\ngo\n");
+    assert_eq!(engine_moves(&lines), ["a1a8".parse().unwrap()]);
+    assert_eq!(lines.last().unwrap(), "1-0 {White mates}");
+}
+
+#[test]
+fn thinking_output() {
+    let lines =
+        script("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1\npost\nsd 2\ngo\n");
+    assert!(lines.iter().any(|line| line.starts_with("1 100001 ")));
+}
+
+#[test]
+fn illegal_move() {
+    let lines = script("new\nforce\nusermove e2e5\nsetboard 8/8/8/8/8/8/8/8 w - - 0 1\n");
+    assert_eq!(
+        lines,
+        ["Illegal move: e2e5", "tellusererror Illegal position"]
+    );
+}
+
+#[test]
+fn undo_and_remove() {
+    // After taking back both moves the engine, playing black, sees 1. d4 instead
+    let lines = script(
+        "new\nforce\nusermove e2e4\nusermove e7e5\nremove\nplayother\nsd 1\nusermove d2d4\n",
+    );
+    let mut board = Board::default();
+    board.make_move("d2d4".parse().unwrap()).unwrap();
+    assert!(board.legal_moves().contains(&engine_moves(&lines)[0]));
+
+    let lines = script("new\nforce\nusermove e2e4\nundo\nusermove e2e4\n");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn move_now() {
+    let lines = script("new\ntime 100000\notim 100000\nusermove e2e4\n?\nping 2\n");
+    assert_eq!(engine_moves(&lines).len(), 1);
+    assert_eq!(lines.last().unwrap(), "pong 2");
+}
+
+#[test]
+fn user_delivers_mate() {
+    let lines =
+        script("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1\nusermove a1a8\n");
+    assert_eq!(lines, ["1-0 {White mates}"]);
+}
+
+#[test]
+fn bad_level() {
+    let lines = script("level 40 5 -1\nlevel 40 5 nan\nping 1\n");
+    assert_eq!(
+        lines,
+        [
+            "Error (bad level): level 40 5 -1",
+            "Error (bad level): level 40 5 nan",
+            "pong 1",
+        ]
+    );
+}
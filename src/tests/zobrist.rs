@@ -0,0 +1,59 @@
+use crate::*;
+
+#[test]
+fn equal_positions_hash_equal() {
+    assert_eq!(
+        Board::default().zobrist_hash(),
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+            .unwrap()
+            .zobrist_hash()
+    );
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn transpositions_hash_equal() {
+    let play = |moves: &[&str]| {
+        let mut board = Board::default();
+        for m in moves {
+            board.make_move(m.parse().unwrap()).unwrap();
+        }
+        board.zobrist_hash()
+    };
+    assert_eq!(
+        play(&["g1f3", "g8f6", "b1c3"]),
+        play(&["b1c3", "g8f6", "g1f3"])
+    );
+    assert_ne!(play(&["g1f3"]), play(&["b1c3"]));
+}
+
+#[test]
+fn side_castling_and_en_passant_count() {
+    let hash = |fen: &str| Board::from_fen(fen).unwrap().zobrist_hash();
+    let base = hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq - 0 1");
+    assert_ne!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R b KQkq - 0 1"));
+    assert_ne!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w Kkq - 0 1"));
+    assert_ne!(base, hash("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1"));
+}
+
+#[test]
+fn rook_files_and_variant_count() {
+    // Same pieces, but castling queenside with the rook on a1 or on b1
+    let outer = Board::from_fen("4k3/8/8/8/8/8/8/RR2K3 w Q - 0 1").unwrap();
+    let inner = Board::from_fen("4k3/8/8/8/8/8/8/RR2K3 w B - 0 1").unwrap();
+    assert_eq!(outer.squares, inner.squares);
+    assert_ne!(outer.zobrist_hash(), inner.zobrist_hash());
+
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    let hashes: Vec<u64> = [Variant::Standard, Variant::Atomic, Variant::Antichess]
+        .into_iter()
+        .map(|variant| {
+            Board::from_variant_fen(fen, variant)
+                .unwrap()
+                .zobrist_hash()
+        })
+        .collect();
+    assert_ne!(hashes[0], hashes[1]);
+    assert_ne!(hashes[1], hashes[2]);
+    assert_ne!(hashes[0], hashes[2]);
+}
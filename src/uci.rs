@@ -0,0 +1,257 @@
+//! The Universal Chess Interface, which GUIs like Cute Chess and Arena use to talk to engines.
+//!
+//! [`run`] reads commands line by line and writes the replies, searching on a separate thread so
+//! `stop` and `isready` are answered while the engine thinks.
+
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::engine::{Engine, Limits, SearchResult, DEFAULT_HASH_MB};
+use crate::{Board, Color, Move};
+
+pub(crate) type Output = Arc<Mutex<Box<dyn Write + Send>>>;
+
+pub(crate) fn send(output: &Output, line: &str) -> io::Result<()> {
+    let mut output = output.lock().unwrap();
+    writeln!(output, "{}", line)?;
+    output.flush()
+}
+
+fn info(result: &SearchResult, elapsed: Duration) -> String {
+    let millis = elapsed.as_millis().max(1) as u64;
+    let mut line = format!(
+        "info depth {} score {} nodes {} nps {} time {}",
+        result.depth,
+        result.score,
+        result.nodes,
+        result.nodes * 1000 / millis,
+        millis
+    );
+    if !result.pv.is_empty() {
+        line.push_str(" pv");
+        for m in &result.pv {
+            line.push_str(&format!(" {}", m));
+        }
+    }
+    line
+}
+
+fn best_move(result: &SearchResult) -> String {
+    match (result.best_move, result.pv.get(1)) {
+        (Some(best), Some(ponder)) => format!("bestmove {} ponder {}", best, ponder),
+        (Some(best), None) => format!("bestmove {}", best),
+        // UCI's null move, for positions without any legal moves
+        (None, _) => "bestmove 0000".to_string(),
+    }
+}
+
+struct Search {
+    stop: Arc<AtomicBool>,
+    infinite: bool,
+    handle: JoinHandle<Engine>,
+}
+
+struct Uci {
+    board: Board,
+    /// `None` while a search has it.
+    engine: Option<Engine>,
+    search: Option<Search>,
+    output: Output,
+    /// Whether the GUI set UCI_Chess960, so castling is written as the king taking its rook.
+    chess960: bool,
+}
+
+impl Uci {
+    fn send(&self, line: &str) -> io::Result<()> {
+        send(&self.output, line)
+    }
+
+    /// Waits for the running search to finish, first telling it to stop if `stop` is set.
+    fn wait(&mut self, stop: bool) {
+        if let Some(search) = self.search.take() {
+            if stop {
+                search.stop.store(true, Ordering::Relaxed);
+            }
+            self.engine = Some(search.handle.join().unwrap());
+        }
+    }
+
+    fn engine(&mut self) -> &mut Engine {
+        self.wait(true);
+        self.engine.as_mut().unwrap()
+    }
+
+    /// Handles one command, returning whether to keep going.
+    fn command(&mut self, line: &str) -> io::Result<bool> {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uci") => {
+                self.send(&format!("id name rust-chess {}", env!("CARGO_PKG_VERSION")))?;
+                self.send("id author the rust-chess developers")?;
+                self.send(&format!(
+                    "option name Hash type spin default {} min 1 max 4096",
+                    DEFAULT_HASH_MB
+                ))?;
+                self.send("option name UCI_Chess960 type check default false")?;
+                self.send("uciok")?;
+            }
+            Some("isready") => self.send("readyok")?,
+            Some("ucinewgame") => {
+                self.engine().table.clear();
+                self.board = Board::default();
+                self.board.set_chess960(self.chess960);
+            }
+            Some("setoption") => self.set_option(tokens.collect())?,
+            Some("position") => self.position(tokens.collect())?,
+            Some("go") => self.go(tokens.collect()),
+            Some("stop") => self.wait(true),
+            Some("quit") => return Ok(false),
+            // Accepted, but there's nothing to do for them
+            Some("debug" | "register" | "ponderhit") | None => {}
+            Some(command) => self.send(&format!("info string unknown command {}", command))?,
+        }
+        Ok(true)
+    }
+
+    fn set_option(&mut self, tokens: Vec<&str>) -> io::Result<()> {
+        let value = tokens.iter().position(|token| *token == "value");
+        let name = tokens[..value.unwrap_or(tokens.len())]
+            .iter()
+            .skip_while(|token| **token == "name")
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = value.and_then(|index| tokens.get(index + 1));
+        match (name.to_lowercase().as_str(), value.copied()) {
+            ("hash", Some(megabytes)) if megabytes.parse::<usize>().is_ok() => {
+                self.engine().table.resize(megabytes.parse().unwrap())
+            }
+            ("uci_chess960", Some(enabled @ ("true" | "false"))) => {
+                self.chess960 = enabled == "true";
+                self.board.set_chess960(self.chess960);
+            }
+            _ => self.send(&format!("info string unsupported option {}", name))?,
+        }
+        Ok(())
+    }
+
+    fn position(&mut self, tokens: Vec<&str>) -> io::Result<()> {
+        let moves = tokens.iter().position(|token| *token == "moves");
+        let setup = &tokens[..moves.unwrap_or(tokens.len())];
+        let mut board = match setup {
+            ["startpos"] => Board::default(),
+            ["fen", fen @ ..] => match Board::from_fen(&fen.join(" ")) {
+                Ok(board) => board,
+                Err(_) => return self.send("info string invalid FEN"),
+            },
+            _ => return self.send("info string invalid position"),
+        };
+        // A FEN with Chess960 castling rights is one even if the GUI didn't say so
+        let chess960 = self.chess960 || board.is_chess960();
+        board.set_chess960(chess960);
+        // The current position only changes once every move has been played
+        for token in moves.map_or(&[][..], |index| &tokens[index + 1..]) {
+            let played = token
+                .parse::<Move>()
+                .ok()
+                .and_then(|m| board.make_move(m).ok());
+            if played.is_none() {
+                return self.send(&format!("info string illegal move {}", token));
+            }
+        }
+        self.board = board;
+        Ok(())
+    }
+
+    fn go(&mut self, tokens: Vec<&str>) {
+        let mut limits = Limits::default();
+        let mut clock = [None; 2];
+        let mut increment = [0; 2];
+        let mut moves_to_go = None;
+        let mut infinite = tokens.is_empty();
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            let mut value = || tokens.next().and_then(|value| value.parse::<u64>().ok());
+            match token {
+                "depth" => limits.depth = value().map(|depth| depth as u32),
+                "nodes" => limits.nodes = value(),
+                "movetime" => limits.time = value().map(Duration::from_millis),
+                "wtime" => clock[0] = value(),
+                "btime" => clock[1] = value(),
+                "winc" => increment[0] = value().unwrap_or(0),
+                "binc" => increment[1] = value().unwrap_or(0),
+                "movestogo" => moves_to_go = value().map(|moves| moves as u32),
+                "infinite" | "ponder" => infinite = true,
+                _ => {}
+            }
+        }
+        let side = match self.board.side_to_move() {
+            Color::White => 0,
+            Color::Black => 1,
+        };
+        if let (None, Some(time)) = (limits.time, clock[side]) {
+            limits.time = Limits::clock(
+                Duration::from_millis(time),
+                Duration::from_millis(increment[side]),
+                moves_to_go,
+            )
+            .time;
+        }
+
+        self.wait(true);
+        let mut engine = self.engine.take().unwrap();
+        let board = self.board.clone();
+        let output = self.output.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let start = Instant::now();
+                let result = engine.search_with(&board, limits, &stop, |result| {
+                    send(&output, &info(result, start.elapsed())).ok();
+                });
+                // An infinite search only reports its move once it's told to stop
+                while infinite && !stop.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(5));
+                }
+                match result {
+                    Ok(result) => send(&output, &best_move(&result)).ok(),
+                    Err(_) => {
+                        send(&output, "info string unsupported variant").ok();
+                        send(&output, "bestmove 0000").ok()
+                    }
+                };
+                engine
+            }
+        });
+        self.search = Some(Search {
+            stop,
+            infinite,
+            handle,
+        });
+    }
+}
+
+/// Runs the protocol until `quit` or the end of `input`. At the end of the input a running
+/// search gets to finish, unless it's an infinite one.
+pub fn run(input: impl BufRead, output: impl Write + Send + 'static) -> io::Result<()> {
+    let mut uci = Uci {
+        board: Board::default(),
+        engine: Some(Engine::default()),
+        search: None,
+        output: Arc::new(Mutex::new(Box::new(output))),
+        chess960: false,
+    };
+    for line in input.lines() {
+        if !uci.command(&line?)? {
+            uci.wait(true);
+            return Ok(());
+        }
+    }
+    let infinite = uci.search.as_ref().is_some_and(|search| search.infinite);
+    uci.wait(infinite);
+    Ok(())
+}
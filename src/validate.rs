@@ -0,0 +1,171 @@
+use crate::{Board, Color, File, Piece, Position, Rank, Variant};
+
+/// Something about a board's internals that doesn't add up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// There's no square stored for this position.
+    MissingSquare(Position),
+    /// The square stored under `key` claims to be at `position`.
+    SquarePosition { key: Position, position: Position },
+    /// The troop standing on `square` claims to be at `position`.
+    TroopPosition {
+        square: Position,
+        position: Position,
+    },
+    /// A side doesn't have exactly one king when the variant needs one. Variants that don't,
+    /// like Antichess, allow any number.
+    KingCount { color: Color, count: usize },
+    /// The tracked king position for a side doesn't hold that side's king.
+    KingPosition { color: Color, position: Position },
+    /// A side has a king on the board, but isn't tracked as having one.
+    UntrackedKing(Color),
+    /// A pawn stands on the first or last rank, where it could never have got to or stayed on.
+    /// Horde starts white pawns on the first rank, so those are allowed there.
+    PawnOnBackRank(Position),
+}
+
+impl std::fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Inconsistency::MissingSquare(position) => write!(f, "square {} is missing", position),
+            Inconsistency::SquarePosition { key, position } => {
+                write!(f, "square {} claims to be at {}", key, position)
+            }
+            Inconsistency::TroopPosition { square, position } => {
+                write!(f, "troop on {} claims to be at {}", square, position)
+            }
+            Inconsistency::KingCount { color, count } => {
+                write!(f, "{:?} has {} kings", color, count)
+            }
+            Inconsistency::KingPosition { color, position } => {
+                write!(
+                    f,
+                    "{:?} king is tracked on {}, but isn't there",
+                    color, position
+                )
+            }
+            Inconsistency::UntrackedKing(color) => {
+                write!(f, "{:?} has a king that isn't tracked", color)
+            }
+            Inconsistency::PawnOnBackRank(position) => {
+                write!(f, "pawn on back rank at {}", position)
+            }
+        }
+    }
+}
+
+impl Board {
+    /// Checks that the board's internals agree with each other, reporting every inconsistency
+    /// found. Boards built by this crate are always consistent, but deserialized ones might not
+    /// be, and an inconsistent board can panic when used.
+    pub fn validate(&self) -> Result<(), Vec<Inconsistency>> {
+        let mut inconsistencies = vec![];
+        let mut white_kings = 0;
+        let mut black_kings = 0;
+
+        for file in 1..=8 {
+            for rank in 1..=8 {
+                let key = Position {
+                    file: File::try_from(file).unwrap(),
+                    rank: Rank::try_from(rank).unwrap(),
+                };
+                let Some(square) = self
+                    .squares
+                    .get(&key.file)
+                    .and_then(|rank_map| rank_map.get(&key.rank))
+                else {
+                    inconsistencies.push(Inconsistency::MissingSquare(key));
+                    continue;
+                };
+                if square.position != key {
+                    inconsistencies.push(Inconsistency::SquarePosition {
+                        key,
+                        position: square.position,
+                    });
+                }
+                if let Some(troop) = &square.troop {
+                    if troop.position != key {
+                        inconsistencies.push(Inconsistency::TroopPosition {
+                            square: key,
+                            position: troop.position,
+                        });
+                    }
+                    let horde_start = self.variant == Variant::Horde
+                        && troop.color == Color::White
+                        && key.rank == Rank::One;
+                    if troop.piece == Piece::Pawn
+                        && matches!(key.rank, Rank::One | Rank::Eight)
+                        && !horde_start
+                    {
+                        inconsistencies.push(Inconsistency::PawnOnBackRank(key));
+                    }
+                    if troop.piece == Piece::King {
+                        match troop.color {
+                            Color::White => white_kings += 1,
+                            Color::Black => black_kings += 1,
+                        }
+                    }
+                }
+            }
+        }
+
+        for (color, count, position) in [
+            (Color::White, white_kings, self.white_king),
+            (Color::Black, black_kings, self.black_king),
+        ] {
+            if count != 1 && self.variant.needs_king(color) {
+                inconsistencies.push(Inconsistency::KingCount { color, count });
+                continue;
+            }
+            let Some(position) = position else {
+                if count > 0 {
+                    inconsistencies.push(Inconsistency::UntrackedKing(color));
+                }
+                continue;
+            };
+            let tracked = self
+                .squares
+                .get(&position.file)
+                .and_then(|rank_map| rank_map.get(&position.rank))
+                .and_then(|square| square.troop.as_ref());
+            match tracked {
+                Some(troop) if troop.piece == Piece::King && troop.color == color => {}
+                _ => inconsistencies.push(Inconsistency::KingPosition { color, position }),
+            }
+        }
+
+        if inconsistencies.is_empty() {
+            Ok(())
+        } else {
+            Err(inconsistencies)
+        }
+    }
+
+    /// Deserializes a board and rejects it unless [`Board::validate`] passes. Can be used with
+    /// `#[serde(deserialize_with = "Board::deserialize_checked")]`.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_checked<'de, D>(deserializer: D) -> Result<Board, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let board = <Board as serde::Deserialize>::deserialize(deserializer)?;
+        match board.validate() {
+            Ok(()) => Ok(board),
+            Err(inconsistencies) => Err(serde::de::Error::custom(
+                inconsistencies
+                    .iter()
+                    .map(|inconsistency| inconsistency.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )),
+        }
+    }
+
+    /// Parses a board serialized as JSON, rejecting it unless [`Board::validate`] passes.
+    #[cfg(feature = "serde")]
+    pub fn from_json_checked(json: &str) -> Result<Board, crate::Error> {
+        let board: Board = serde_json::from_str(json).map_err(|_| crate::Error::BoardParse)?;
+        board.validate().map_err(crate::Error::InvalidBoard)?;
+        Ok(board)
+    }
+}
@@ -0,0 +1,205 @@
+//! Rule sets other than standard chess. Each variant plays on the same [`Board`] with the same
+//! move generator, and only changes the starting position, which moves are allowed on top of
+//! the usual ones, and how the game ends.
+
+use crate::{Board, Color};
+#[cfg(feature = "actions")]
+use crate::{BoardState, File, Move, Position, Rank, Troop};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    #[default]
+    Standard,
+    /// Bringing the king to one of the four center squares wins.
+    KingOfTheHill,
+    /// Giving check for the third time wins.
+    ThreeCheck,
+    /// Both sides race their kings to the eighth rank, and no move may give check. If white
+    /// gets there first, black has one move to draw by getting there too.
+    RacingKings,
+    /// Captured pieces join the capturer's side, to be dropped back on the board as a move.
+    Crazyhouse,
+    /// Crazyhouse on two boards, where captures go to the partner on the other board. See
+    /// [`crate::Bughouse`].
+    Bughouse,
+    /// Captures explode, taking the capturing piece and every piece but pawns around the square
+    /// with them. Blowing up the enemy king wins.
+    Atomic,
+    /// Captures are compulsory and the king is just another piece. Losing every piece, or
+    /// having no move left, wins.
+    Antichess,
+    /// White has a horde of 36 pawns and no king against black's usual army. Black wins by
+    /// capturing all of them, white by checkmating as usual. Pawns on the first rank may step
+    /// two squares, though not to be taken en passant.
+    Horde,
+}
+
+#[cfg(feature = "actions")]
+const HILL: [(File, Rank); 4] = [
+    (File::D, Rank::Four),
+    (File::E, Rank::Four),
+    (File::D, Rank::Five),
+    (File::E, Rank::Five),
+];
+
+impl Variant {
+    /// The position games of this variant start from.
+    pub fn starting_position(self) -> Board {
+        let fen = match self {
+            Variant::RacingKings => "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1",
+            Variant::Antichess => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            Variant::Horde => {
+                "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1"
+            }
+            _ => {
+                let mut board = Board::default();
+                board.set_variant(self);
+                return board;
+            }
+        };
+        Board::from_variant_fen(fen, self).unwrap()
+    }
+
+    /// The name lichess and PGN `Variant` tags use.
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Standard => "Standard",
+            Variant::KingOfTheHill => "King of the Hill",
+            Variant::ThreeCheck => "Three-check",
+            Variant::RacingKings => "Racing Kings",
+            Variant::Crazyhouse => "Crazyhouse",
+            Variant::Bughouse => "Bughouse",
+            Variant::Atomic => "Atomic",
+            Variant::Antichess => "Antichess",
+            Variant::Horde => "Horde",
+        }
+    }
+
+    /// Whether `color` has to have a king. White never does in Horde, and in Antichess the king
+    /// is just another piece that may be lost.
+    pub fn needs_king(self, color: Color) -> bool {
+        match self {
+            Variant::Horde => color == Color::Black,
+            Variant::Antichess => false,
+            _ => true,
+        }
+    }
+}
+
+impl Board {
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Whether the variant has pockets and drop moves.
+    pub(crate) fn drops(&self) -> bool {
+        matches!(self.variant, Variant::Crazyhouse | Variant::Bughouse)
+    }
+
+    /// How many times `color` has given check, which only Three-check keeps track of.
+    pub fn checks_given(&self, color: Color) -> u8 {
+        self.checks[color as usize]
+    }
+
+    /// Plays the rest of the game by `variant`'s rules, keeping the position as it is.
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+        // A mate under one set of rules needn't be one under another
+        #[cfg(feature = "actions")]
+        self.update_state(self.side_to_move());
+    }
+}
+
+#[cfg(feature = "actions")]
+impl Board {
+    /// Whether the variant allows `troop` to go to `to`, on top of the standard rules.
+    pub(crate) fn variant_allows(&self, troop: &Troop, to: Position) -> bool {
+        match self.variant {
+            Variant::RacingKings => {
+                let mut after = self.clone();
+                after.apply_move(Move {
+                    from: troop.position,
+                    to,
+                    promotion: None,
+                });
+                !after.check_for_check(troop.color.opponent())
+            }
+            Variant::Standard
+            | Variant::KingOfTheHill
+            | Variant::ThreeCheck
+            | Variant::Crazyhouse
+            | Variant::Bughouse
+            | Variant::Atomic
+            | Variant::Antichess
+            | Variant::Horde => true,
+        }
+    }
+
+    /// Keeps count of checks for Three-check after `color` moved.
+    pub(crate) fn count_check(&mut self, color: Color) {
+        if self.variant == Variant::ThreeCheck && self.check_for_check(color.opponent()) {
+            self.checks[color as usize] += 1;
+        }
+    }
+
+    /// How the game ends by the variant's own rules now that it's `color`'s turn, if it does.
+    pub(crate) fn variant_outcome(&self, color: Color) -> Option<BoardState> {
+        let mover = color.opponent();
+        let on_rank =
+            |color: Color, rank: Rank| self.king(color).is_some_and(|king| king.rank == rank);
+        match self.variant {
+            Variant::Standard | Variant::Crazyhouse | Variant::Bughouse => None,
+            Variant::Atomic => match (self.king_alive(color), self.king_alive(mover)) {
+                (false, _) => Some(BoardState::VariantLoss(color)),
+                (true, false) => Some(BoardState::VariantWin(color)),
+                (true, true) => None,
+            },
+            // With no pieces left there are no moves either
+            Variant::Antichess => self
+                .legal_moves()
+                .is_empty()
+                .then_some(BoardState::VariantWin(color)),
+            Variant::KingOfTheHill => self
+                .king(mover)
+                .is_some_and(|king| HILL.contains(&(king.file, king.rank)))
+                .then_some(BoardState::VariantLoss(color)),
+            Variant::ThreeCheck => {
+                (self.checks_given(mover) >= 3).then_some(BoardState::VariantLoss(color))
+            }
+            Variant::RacingKings => {
+                let white = on_rank(Color::White, Rank::Eight);
+                let black = on_rank(Color::Black, Rank::Eight);
+                match (white, black) {
+                    (true, true) => Some(BoardState::Draw),
+                    (false, true) => Some(BoardState::VariantLoss(Color::White)),
+                    (true, false) if color == Color::Black => {
+                        // Black still gets a move to catch up
+                        let catches_up = self.king(Color::Black).is_some_and(|king| {
+                            let king = self.get_square(&king).troop.clone().unwrap();
+                            self.legal_destinations(&king)
+                                .iter()
+                                .any(|to| to.rank == Rank::Eight)
+                        });
+                        (!catches_up).then_some(BoardState::VariantLoss(Color::Black))
+                    }
+                    (true, false) => Some(BoardState::VariantWin(Color::White)),
+                    (false, false) => None,
+                }
+            }
+            Variant::Horde => {
+                let horde_left = self
+                    .squares
+                    .values()
+                    .flat_map(|rank_map| rank_map.values())
+                    .filter_map(|square| square.troop.as_ref())
+                    .any(|troop| troop.color == Color::White);
+                match (horde_left, color) {
+                    (true, _) => None,
+                    (false, Color::White) => Some(BoardState::VariantLoss(Color::White)),
+                    (false, Color::Black) => Some(BoardState::VariantWin(Color::Black)),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! The JSON format boards are exchanged in with clients.
+//!
+//! Unlike serializing [`Board`] directly, this format only depends on the rules of chess, not on
+//! the crate's internals. A payload looks like this:
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "variant": "standard",
+//!   "fen": "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+//!   "state": { "kind": "to_move", "color": "black" },
+//!   "legal_moves": ["a7a5", "a7a6", "..."]
+//! }
+//! ```
+//!
+//! The FEN is read by the rules of `variant`, and `legal_moves` are in coordinate notation. The
+//! `version` is bumped whenever the format changes incompatibly.
+
+use serde::{Deserialize, Serialize};
+
+use crate::variant::Variant;
+use crate::{Board, BoardState, Color, Error};
+
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WireError {
+    Json,
+    UnsupportedVersion,
+    StateMismatch,
+    LegalMovesMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardDto {
+    pub version: u32,
+    pub variant: VariantDto,
+    pub fen: String,
+    pub state: StateDto,
+    pub legal_moves: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "color", rename_all = "snake_case")]
+pub enum StateDto {
+    ToMove(ColorDto),
+    Check(ColorDto),
+    Checkmate(ColorDto),
+    Stalemate,
+    Draw,
+    VariantLoss(ColorDto),
+    VariantWin(ColorDto),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorDto {
+    White,
+    Black,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantDto {
+    Standard,
+    KingOfTheHill,
+    ThreeCheck,
+    RacingKings,
+    Crazyhouse,
+    Bughouse,
+    Atomic,
+    Antichess,
+    Horde,
+}
+
+impl From<Color> for ColorDto {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::White => ColorDto::White,
+            Color::Black => ColorDto::Black,
+        }
+    }
+}
+
+impl From<ColorDto> for Color {
+    fn from(color: ColorDto) -> Self {
+        match color {
+            ColorDto::White => Color::White,
+            ColorDto::Black => Color::Black,
+        }
+    }
+}
+
+impl From<Variant> for VariantDto {
+    fn from(variant: Variant) -> Self {
+        match variant {
+            Variant::Standard => VariantDto::Standard,
+            Variant::KingOfTheHill => VariantDto::KingOfTheHill,
+            Variant::ThreeCheck => VariantDto::ThreeCheck,
+            Variant::RacingKings => VariantDto::RacingKings,
+            Variant::Crazyhouse => VariantDto::Crazyhouse,
+            Variant::Bughouse => VariantDto::Bughouse,
+            Variant::Atomic => VariantDto::Atomic,
+            Variant::Antichess => VariantDto::Antichess,
+            Variant::Horde => VariantDto::Horde,
+        }
+    }
+}
+
+impl From<VariantDto> for Variant {
+    fn from(variant: VariantDto) -> Self {
+        match variant {
+            VariantDto::Standard => Variant::Standard,
+            VariantDto::KingOfTheHill => Variant::KingOfTheHill,
+            VariantDto::ThreeCheck => Variant::ThreeCheck,
+            VariantDto::RacingKings => Variant::RacingKings,
+            VariantDto::Crazyhouse => Variant::Crazyhouse,
+            VariantDto::Bughouse => Variant::Bughouse,
+            VariantDto::Atomic => Variant::Atomic,
+            VariantDto::Antichess => Variant::Antichess,
+            VariantDto::Horde => Variant::Horde,
+        }
+    }
+}
+
+impl From<&BoardState> for StateDto {
+    fn from(state: &BoardState) -> Self {
+        match state {
+            BoardState::ToMove(color) => StateDto::ToMove((*color).into()),
+            BoardState::Check(color) => StateDto::Check((*color).into()),
+            BoardState::Checkmate(color) => StateDto::Checkmate((*color).into()),
+            BoardState::Stalemate => StateDto::Stalemate,
+            BoardState::Draw => StateDto::Draw,
+            BoardState::VariantLoss(color) => StateDto::VariantLoss((*color).into()),
+            BoardState::VariantWin(color) => StateDto::VariantWin((*color).into()),
+        }
+    }
+}
+
+impl From<StateDto> for BoardState {
+    fn from(state: StateDto) -> Self {
+        match state {
+            StateDto::ToMove(color) => BoardState::ToMove(color.into()),
+            StateDto::Check(color) => BoardState::Check(color.into()),
+            StateDto::Checkmate(color) => BoardState::Checkmate(color.into()),
+            StateDto::Stalemate => BoardState::Stalemate,
+            StateDto::Draw => BoardState::Draw,
+            StateDto::VariantLoss(color) => BoardState::VariantLoss(color.into()),
+            StateDto::VariantWin(color) => BoardState::VariantWin(color.into()),
+        }
+    }
+}
+
+impl From<&Board> for BoardDto {
+    fn from(board: &Board) -> Self {
+        BoardDto {
+            version: SCHEMA_VERSION,
+            variant: board.variant().into(),
+            fen: board.to_fen(),
+            state: (&board.state).into(),
+            legal_moves: board.legal_moves().iter().map(|m| m.to_string()).collect(),
+        }
+    }
+}
+
+impl TryFrom<BoardDto> for Board {
+    type Error = Error;
+
+    /// Rebuilds the board from its FEN by its variant's rules, checking that the rest of the
+    /// payload agrees with it.
+    fn try_from(dto: BoardDto) -> Result<Self, Self::Error> {
+        if dto.version != SCHEMA_VERSION {
+            return Err(Error::Wire(WireError::UnsupportedVersion));
+        }
+        let mut board = Board::from_variant_fen(&dto.fen, dto.variant.into())?;
+
+        let mut legal_moves = board
+            .legal_moves()
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>();
+        let state = BoardState::from(dto.state);
+        match (&board.state, &state) {
+            (BoardState::ToMove(_) | BoardState::Check(_), BoardState::Draw) => {}
+            (expected, state) if expected == state => {}
+            _ => return Err(Error::Wire(WireError::StateMismatch)),
+        }
+        board.state = state;
+
+        if let BoardState::Draw = board.state {
+            legal_moves.clear();
+        }
+        let mut claimed_moves = dto.legal_moves;
+        legal_moves.sort();
+        claimed_moves.sort();
+        if legal_moves != claimed_moves {
+            return Err(Error::Wire(WireError::LegalMovesMismatch));
+        }
+
+        Ok(board)
+    }
+}
+
+pub fn to_json(board: &Board) -> String {
+    serde_json::to_string(&BoardDto::from(board)).unwrap()
+}
+
+pub fn from_json(json: &str) -> Result<Board, Error> {
+    let dto: BoardDto = serde_json::from_str(json).map_err(|_| Error::Wire(WireError::Json))?;
+    Board::try_from(dto)
+}
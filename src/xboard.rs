@@ -0,0 +1,319 @@
+//! The Chess Engine Communication Protocol that XBoard and WinBoard speak.
+//!
+//! [`run`] reads commands line by line and writes the replies. Unlike UCI the engine keeps
+//! track of the game itself: it answers the opponent's moves on its own unless it's in force
+//! mode, and thinks on a separate thread so `?` can make it move right away.
+
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::engine::{Engine, Limits, Score, SearchResult};
+use crate::uci::{send, Output};
+use crate::{Board, BoardState, Color, Move};
+
+/// The time control set by `level`.
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    /// Moves per time control, or 0 for the whole game.
+    moves: u32,
+    increment: Duration,
+}
+
+/// Thinking output: depth, score, time in centiseconds, nodes and the principal variation.
+fn thinking(result: &SearchResult, elapsed: Duration) -> String {
+    let score = match result.score {
+        Score::Centipawns(centipawns) => centipawns,
+        // XBoard's convention for mate in N
+        Score::Mate(moves) if moves > 0 => 100_000 + moves,
+        Score::Mate(moves) => -100_000 + moves,
+    };
+    let mut line = format!(
+        "{} {} {} {}",
+        result.depth,
+        score,
+        elapsed.as_millis() / 10,
+        result.nodes
+    );
+    for m in &result.pv {
+        line.push_str(&format!(" {}", m));
+    }
+    line
+}
+
+/// The result line for a finished game, if it's over.
+fn game_result(board: &Board) -> Option<&'static str> {
+    match board.state {
+        BoardState::Checkmate(Color::Black) => Some("1-0 {White mates}"),
+        BoardState::Checkmate(Color::White) => Some("0-1 {Black mates}"),
+        BoardState::Stalemate => Some("1/2-1/2 {Stalemate}"),
+        BoardState::Draw => Some("1/2-1/2 {Draw}"),
+        BoardState::VariantLoss(Color::Black) | BoardState::VariantWin(Color::White) => {
+            Some("1-0 {White wins}")
+        }
+        BoardState::VariantLoss(Color::White) | BoardState::VariantWin(Color::Black) => {
+            Some("0-1 {Black wins}")
+        }
+        BoardState::ToMove(_) | BoardState::Check(_) => None,
+    }
+}
+
+struct Search {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<(Engine, Option<Move>)>,
+}
+
+struct XBoard {
+    board: Board,
+    /// The positions before each move played, for `undo`.
+    history: Vec<Board>,
+    /// The side the engine plays, or `None` in force mode.
+    engine_color: Option<Color>,
+    /// `None` while a search has it.
+    engine: Option<Engine>,
+    search: Option<Search>,
+    level: Level,
+    /// Centiseconds left on the engine's clock, from `time`.
+    clock: Option<u64>,
+    move_time: Option<Duration>,
+    depth: Option<u32>,
+    post: bool,
+    output: Output,
+}
+
+impl XBoard {
+    fn send(&self, line: &str) -> io::Result<()> {
+        send(&self.output, line)
+    }
+
+    /// Waits for the engine to finish thinking and plays its move, first telling it to move
+    /// right away if `stop` is set.
+    fn wait(&mut self, stop: bool) {
+        if let Some(search) = self.search.take() {
+            if stop {
+                search.stop.store(true, Ordering::Relaxed);
+            }
+            let (engine, best_move) = search.handle.join().unwrap();
+            self.engine = Some(engine);
+            if let Some(m) = best_move {
+                self.history.push(self.board.clone());
+                self.board.make_move(m).unwrap();
+            }
+        }
+    }
+
+    fn limits(&self) -> Limits {
+        let mut limits = Limits::default();
+        if let Some(time) = self.move_time {
+            limits.time = Some(time);
+        } else if let Some(clock) = self.clock {
+            let moves_to_go = match self.level.moves {
+                0 => None,
+                moves => Some(moves - (self.history.len() as u32 / 2) % moves),
+            };
+            limits.time = Limits::clock(
+                Duration::from_millis(clock * 10),
+                self.level.increment,
+                moves_to_go,
+            )
+            .time;
+        } else if self.depth.is_none() {
+            // Nobody said how long to think, so don't think for long
+            limits.time = Some(Duration::from_secs(5));
+        }
+        limits.depth = self.depth;
+        limits
+    }
+
+    /// Starts thinking about a move if it's the engine's turn.
+    fn think(&mut self) {
+        if self.engine_color != Some(self.board.side_to_move())
+            || game_result(&self.board).is_some()
+        {
+            return;
+        }
+        let limits = self.limits();
+        let mut engine = self.engine.take().unwrap();
+        let board = self.board.clone();
+        let output = self.output.clone();
+        let post = self.post;
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let start = Instant::now();
+                let result = engine.search_with(&board, limits, &stop, |result| {
+                    if post {
+                        send(&output, &thinking(result, start.elapsed())).ok();
+                    }
+                });
+                let Ok(result) = result else {
+                    send(&output, "tellusererror Unsupported variant").ok();
+                    return (engine, None);
+                };
+                if let Some(m) = result.best_move {
+                    send(&output, &format!("move {}", m)).ok();
+                    let mut board = board.clone();
+                    board.make_move(m).unwrap();
+                    if let Some(result) = game_result(&board) {
+                        send(&output, result).ok();
+                    }
+                }
+                (engine, result.best_move)
+            }
+        });
+        self.search = Some(Search { stop, handle });
+    }
+
+    fn user_move(&mut self, token: &str) -> io::Result<()> {
+        let played = token.parse::<Move>().ok().and_then(|m| {
+            let before = self.board.clone();
+            self.board.make_move(m).ok().map(|_| before)
+        });
+        match played {
+            Some(before) => {
+                self.history.push(before);
+                if let Some(result) = game_result(&self.board) {
+                    return self.send(result);
+                }
+                self.think();
+                Ok(())
+            }
+            None => self.send(&format!("Illegal move: {}", token)),
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(board) = self.history.pop() {
+            self.board = board;
+        }
+    }
+
+    /// Handles one command, returning whether to keep going.
+    fn command(&mut self, line: &str) -> io::Result<bool> {
+        let mut tokens = line.split_whitespace();
+        let command = tokens.next();
+        let arguments: Vec<&str> = tokens.collect();
+        match command {
+            Some("ping") => {
+                return self
+                    .send(&format!("pong {}", arguments.join(" ")))
+                    .map(|_| true)
+            }
+            Some("quit") => {
+                self.wait(true);
+                return Ok(false);
+            }
+            // These don't touch the game, so the engine can keep thinking
+            Some(
+                "xboard" | "protover" | "accepted" | "rejected" | "post" | "nopost" | "time"
+                | "otim" | "level" | "st" | "sd" | "random" | "hard" | "easy",
+            )
+            | None => {}
+            // Everything else does, and `?` asks for the move right away
+            Some(_) => self.wait(true),
+        }
+        match (command, &arguments[..]) {
+            (Some("xboard" | "?" | "accepted" | "rejected" | "random" | "hard" | "easy"), _)
+            | (None, _) => {}
+            (Some("protover"), _) => self.send(&format!(
+                "feature myname=\"rust-chess {}\" usermove=1 setboard=1 ping=1 sigint=0 \
+                 sigterm=0 colors=0 done=1",
+                env!("CARGO_PKG_VERSION")
+            ))?,
+            (Some("new"), _) => {
+                self.board = Board::default();
+                self.history.clear();
+                self.engine_color = Some(Color::Black);
+                self.clock = None;
+                self.move_time = None;
+                self.depth = None;
+                self.engine.as_mut().unwrap().table.clear();
+            }
+            (Some("force" | "result"), _) => self.engine_color = None,
+            (Some("go"), _) => {
+                self.engine_color = Some(self.board.side_to_move());
+                self.think();
+            }
+            (Some("playother"), _) => {
+                self.engine_color = Some(self.board.side_to_move().opponent())
+            }
+            (Some("usermove"), [m]) => self.user_move(m)?,
+            (Some("undo"), _) => self.undo(),
+            (Some("remove"), _) => {
+                self.undo();
+                self.undo();
+            }
+            (Some("setboard"), fen) => match Board::from_fen(&fen.join(" ")) {
+                Ok(board) => {
+                    self.board = board;
+                    self.history.clear();
+                }
+                Err(_) => self.send("tellusererror Illegal position")?,
+            },
+            (Some("level"), [moves, base, increment]) => {
+                match (
+                    moves.parse(),
+                    base.split(':').next().map(str::parse::<u32>),
+                    increment.parse().map(Duration::try_from_secs_f64),
+                ) {
+                    (Ok(moves), Some(Ok(_)), Ok(Ok(increment))) => {
+                        self.level = Level { moves, increment };
+                        self.move_time = None;
+                    }
+                    _ => self.send(&format!("Error (bad level): {}", line))?,
+                }
+            }
+            (Some("st"), [seconds]) => match seconds.parse() {
+                Ok(seconds) => self.move_time = Some(Duration::from_secs(seconds)),
+                Err(_) => self.send(&format!("Error (bad time): {}", line))?,
+            },
+            (Some("sd"), [depth]) => match depth.parse() {
+                Ok(depth) => self.depth = Some(depth),
+                Err(_) => self.send(&format!("Error (bad depth): {}", line))?,
+            },
+            (Some("time"), [centiseconds]) => match centiseconds.parse() {
+                Ok(centiseconds) => self.clock = Some(centiseconds),
+                Err(_) => self.send(&format!("Error (bad time): {}", line))?,
+            },
+            // The opponent's clock doesn't change how long the engine thinks
+            (Some("otim"), _) => {}
+            (Some("post"), _) => self.post = true,
+            (Some("nopost"), _) => self.post = false,
+            // Old XBoards send moves without the usermove prefix
+            (Some(m), []) if m.parse::<Move>().is_ok() => self.user_move(m)?,
+            (Some(command), _) => self.send(&format!("Error (unknown command): {}", command))?,
+        }
+        Ok(true)
+    }
+}
+
+/// Runs the protocol until `quit` or the end of `input`. At the end of the input the engine
+/// gets to finish thinking.
+pub fn run(input: impl BufRead, output: impl Write + Send + 'static) -> io::Result<()> {
+    let mut xboard = XBoard {
+        board: Board::default(),
+        history: vec![],
+        engine_color: Some(Color::Black),
+        engine: Some(Engine::default()),
+        search: None,
+        level: Level {
+            moves: 40,
+            increment: Duration::ZERO,
+        },
+        clock: None,
+        move_time: None,
+        depth: None,
+        post: false,
+        output: Arc::new(Mutex::new(Box::new(output))),
+    };
+    for line in input.lines() {
+        if !xboard.command(&line?)? {
+            return Ok(());
+        }
+    }
+    xboard.wait(false);
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::variant::Variant;
+use crate::{Board, Color, Pocket};
+
+/// One key per piece, color and square, then side to move, the four castling rights, the
+/// eight en passant files, the first three checks each side gives in Three-check, the five
+/// kinds of piece each side can have in a Crazyhouse pocket, the file each castling right's rook
+/// starts on, and every variant but standard chess.
+const KEY_COUNT: usize = 12 * 64 + 1 + 4 + 8 + 2 * 3 + 2 * 5 + 4 * 8 + 8;
+const SIDE_TO_MOVE: usize = 12 * 64;
+const CASTLING: usize = SIDE_TO_MOVE + 1;
+const EN_PASSANT: usize = CASTLING + 4;
+const CHECKS: usize = EN_PASSANT + 8;
+const POCKETS: usize = CHECKS + 2 * 3;
+const ROOK_FILES: usize = POCKETS + 2 * 5;
+const VARIANTS: usize = ROOK_FILES + 4 * 8;
+
+/// Fixed pseudo-random keys, so a position hashes the same in every run.
+const KEYS: [u64; KEY_COUNT] = {
+    let mut keys = [0; KEY_COUNT];
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut index = 0;
+    while index < KEY_COUNT {
+        // splitmix64
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        keys[index] = z ^ (z >> 31);
+        index += 1;
+    }
+    keys
+};
+
+impl Board {
+    /// A 64-bit Zobrist hash of the position: the troops, side to move, castling rights with
+    /// their rooks' files, en passant square, the variant and its checks and pockets. Equal
+    /// positions always hash the same, and different ones almost never do.
+    pub fn zobrist_hash(&self) -> u64 {
+        let mut hash = 0;
+        for troop in self
+            .squares
+            .values()
+            .flat_map(|rank_map| rank_map.values())
+            .filter_map(|square| square.troop.as_ref())
+        {
+            let color = match troop.color {
+                Color::White => 0,
+                Color::Black => 1,
+            };
+            let piece = troop.piece as usize * 2 + color;
+            hash ^= KEYS[piece * 64 + troop.position.index() as usize];
+        }
+        if self.side_to_move() == Color::Black {
+            hash ^= KEYS[SIDE_TO_MOVE];
+        }
+        let rooks = self.castling_rooks;
+        let castling = [
+            (self.castling.white_kingside, rooks.white_kingside),
+            (self.castling.white_queenside, rooks.white_queenside),
+            (self.castling.black_kingside, rooks.black_kingside),
+            (self.castling.black_queenside, rooks.black_queenside),
+        ];
+        for (index, (right, file)) in castling.into_iter().enumerate() {
+            if right {
+                hash ^= KEYS[CASTLING + index];
+                hash ^= KEYS[ROOK_FILES + index * 8 + file as usize];
+            }
+        }
+        if let Some(en_passant) = self.en_passant {
+            hash ^= KEYS[EN_PASSANT + en_passant.file as usize];
+        }
+        for (color, checks) in self.checks.into_iter().enumerate() {
+            for check in 0..checks.min(3) as usize {
+                hash ^= KEYS[CHECKS + color * 3 + check];
+            }
+        }
+        for (color, pocket) in self.pockets.iter().enumerate() {
+            for piece in Pocket::PIECES {
+                // Pockets rarely hold many of a piece, so scaling the key by the count is enough
+                let key = KEYS[POCKETS + color * 5 + piece as usize];
+                hash ^= key.wrapping_mul(pocket.count(piece) as u64);
+            }
+        }
+        if self.variant != Variant::Standard {
+            hash ^= KEYS[VARIANTS + self.variant as usize - 1];
+        }
+        hash
+    }
+}